@@ -0,0 +1,82 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod flash_hook {
+	// 测试用的闪电投票回调合约, 只记录被调用的次数和最后一次调用的参数
+	// reject: 为true时拒绝回调, 闪电投票会失败
+	// calls: 被调用的次数
+	// last_call: 最后一次调用的(投票人, 候选人, 票数)
+	#[ink(storage)]
+	pub struct FlashHook {
+		reject: bool,
+		calls: u32,
+		last_call: Option<(AccountId, AccountId, u128)>,
+	}
+
+	impl FlashHook {
+		#[ink(constructor)]
+		pub fn new(reject: bool) -> Self {
+			Self {
+				reject,
+				calls: 0,
+				last_call: None,
+			}
+		}
+
+		// 设置是否拒绝回调, 任何人都可调用, 仅供测试使用
+		#[ink(message)]
+		pub fn set_reject(&mut self, reject: bool) {
+			self.reject = reject;
+		}
+
+		// 闪电投票的回调, 与投票合约中FLASH_HOOK_SELECTOR使用相同的选择器
+		// 返回false表示拒绝
+		#[ink(message, selector = "0xD61F1B19")]
+		pub fn invoke_flash_hook(
+			&mut self,
+			voter: AccountId,
+			candidate: AccountId,
+			amount: u128,
+		) -> bool {
+			self.calls += 1;
+			self.last_call = Some((voter, candidate, amount));
+			!self.reject
+		}
+
+		// 获取被调用的次数
+		#[ink(message)]
+		pub fn calls(&self) -> u32 {
+			self.calls
+		}
+
+		// 获取最后一次调用的参数
+		#[ink(message)]
+		pub fn last_call(&self) -> Option<(AccountId, AccountId, u128)> {
+			self.last_call
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use ink_env::test;
+		type Accounts = test::DefaultAccounts<Environment>;
+		fn default_accounts() -> Accounts {
+			test::default_accounts().expect("Test environment is expected to be initialized.")
+		}
+
+		#[test]
+		fn invoke_flash_hook_works() {
+			let accounts = default_accounts();
+			let mut hook = FlashHook::new(false);
+			assert!(hook.invoke_flash_hook(accounts.alice, accounts.bob, 3));
+			assert_eq!(hook.calls(), 1);
+			assert_eq!(hook.last_call(), Some((accounts.alice, accounts.bob, 3)));
+			hook.set_reject(true);
+			assert!(!hook.invoke_flash_hook(accounts.bob, accounts.alice, 1));
+			assert_eq!(hook.calls(), 2);
+		}
+	}
+}
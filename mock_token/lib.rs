@@ -0,0 +1,139 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod mock_token {
+	use ink_prelude::{string::String, vec::Vec};
+	use ink_storage::collections::HashMap as StorageHashMap;
+
+	// 与PSP22相同的错误类型
+	#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+	pub enum PSP22Error {
+		Custom(String),
+		InsufficientBalance,
+		InsufficientAllowance,
+		ZeroRecipientAddress,
+		ZeroSenderAddress,
+		SafeTransferCheckFailed(String),
+	}
+
+	// 测试用的代币合约, 只实现了PSP22的balance_of, transfer和transfer_from
+	// transfer_from不检查授权, 任何人都可以转走任何账户的代币
+	// balances: 每个账户持有的代币数量
+	#[ink(storage)]
+	pub struct MockToken {
+		balances: StorageHashMap<AccountId, Balance>,
+	}
+
+	impl MockToken {
+		#[ink(constructor)]
+		pub fn new() -> Self {
+			Self {
+				balances: StorageHashMap::default(),
+			}
+		}
+
+		// 设置某账户的余额, 任何人都可调用, 仅供测试使用
+		#[ink(message)]
+		pub fn set_balance(&mut self, owner: AccountId, value: Balance) {
+			self.balances.insert(owner, value);
+		}
+
+		// 查询某账户的余额, 与PSP22::balance_of使用相同的选择器
+		#[ink(message, selector = "0x6568382F")]
+		pub fn balance_of(&self, owner: AccountId) -> Balance {
+			*self.balances.get(&owner).unwrap_or(&0)
+		}
+
+		// 把调用者的代币转给to, 与PSP22::transfer使用相同的选择器
+		#[ink(message, selector = "0xDB20F9F5")]
+		pub fn transfer(
+			&mut self,
+			to: AccountId,
+			value: Balance,
+			_data: Vec<u8>,
+		) -> Result<(), PSP22Error> {
+			let from = self.env().caller();
+			self.transfer_from_to(from, to, value)
+		}
+
+		// 把from的代币转给to, 与PSP22::transfer_from使用相同的选择器
+		#[ink(message, selector = "0x54B3C76E")]
+		pub fn transfer_from(
+			&mut self,
+			from: AccountId,
+			to: AccountId,
+			value: Balance,
+			_data: Vec<u8>,
+		) -> Result<(), PSP22Error> {
+			self.transfer_from_to(from, to, value)
+		}
+
+		fn transfer_from_to(
+			&mut self,
+			from: AccountId,
+			to: AccountId,
+			value: Balance,
+		) -> Result<(), PSP22Error> {
+			let from_balance = self
+				.balance_of(from)
+				.checked_sub(value)
+				.ok_or(PSP22Error::InsufficientBalance)?;
+			self.balances.insert(from, from_balance);
+			let to_balance = self.balance_of(to) + value;
+			self.balances.insert(to, to_balance);
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use ink_env::test;
+		type Accounts = test::DefaultAccounts<Environment>;
+		fn default_accounts() -> Accounts {
+			test::default_accounts().expect("Test environment is expected to be initialized.")
+		}
+
+		#[test]
+		fn default_works() {
+			let accounts = default_accounts();
+			let token = MockToken::new();
+			assert_eq!(token.balance_of(accounts.alice), 0);
+		}
+
+		#[test]
+		fn set_balance_works() {
+			let accounts = default_accounts();
+			let mut token = MockToken::new();
+			token.set_balance(accounts.alice, 3);
+			token.set_balance(accounts.bob, 5);
+			token.set_balance(accounts.alice, 7);
+			assert_eq!(token.balance_of(accounts.alice), 7);
+			assert_eq!(token.balance_of(accounts.bob), 5);
+			assert_eq!(token.balance_of(accounts.charlie), 0);
+		}
+
+		#[test]
+		fn transfer_from_works() {
+			let accounts = default_accounts();
+			let mut token = MockToken::new();
+			token.set_balance(accounts.bob, 10);
+			token
+				.transfer_from(accounts.bob, accounts.alice, 4, Vec::new())
+				.unwrap();
+			assert_eq!(token.balance_of(accounts.bob), 6);
+			assert_eq!(token.balance_of(accounts.alice), 4);
+			token.transfer(accounts.charlie, 3, Vec::new()).unwrap();
+			assert_eq!(token.balance_of(accounts.alice), 1);
+			assert_eq!(token.balance_of(accounts.charlie), 3);
+			assert_eq!(
+				token.transfer(accounts.charlie, 2, Vec::new()),
+				Err(PSP22Error::InsufficientBalance)
+			);
+			assert_eq!(token.balance_of(accounts.alice), 1);
+		}
+	}
+}
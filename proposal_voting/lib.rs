@@ -0,0 +1,299 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod proposal_voting {
+	use ink_prelude::{string::String, vec::Vec};
+	use ink_storage::{
+		collections::HashMap as StorageHashMap,
+		traits::{PackedLayout, SpreadLayout},
+	};
+
+	// 投票相关操作的错误类型
+	#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+	pub enum VotingError {
+		// 提案不存在
+		ProposalNotFound,
+		// 调用者无权限
+		Unauthorized,
+		// 提案数量溢出
+		Overflow,
+	}
+
+	pub type Result<T> = core::result::Result<T, VotingError>;
+
+	// 提案, id由合约按顺序分配
+	#[derive(Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+	#[cfg_attr(
+		feature = "std",
+		derive(
+			scale_info::TypeInfo,
+			ink_storage::traits::StorageLayout,
+			Debug,
+			PartialEq,
+			Eq,
+		)
+	)]
+	pub struct Proposal {
+		id: u32,
+		description: String,
+	}
+
+	// 定义持久化变量
+	// proposals: 提案id对应的提案
+	// votes_received: 每个提案获得的投票数量
+	// next_proposal_id: 下一个提案的id, 只增不减
+	// admin: 管理员, 可在部署后添加提案
+	#[ink(storage)]
+	pub struct Voting {
+		proposals: StorageHashMap<u32, Proposal>,
+		votes_received: StorageHashMap<u32, u32>,
+		next_proposal_id: u32,
+		admin: AccountId,
+	}
+
+	// 投票触发事件定义
+	#[ink(event)]
+	pub struct VoteEvent {
+		#[ink(topic)]
+		from: AccountId,
+		#[ink(topic)]
+		proposal_id: u32,
+	}
+
+	// 添加提案触发事件定义
+	#[ink(event)]
+	pub struct ProposalAdded {
+		#[ink(topic)]
+		proposal_id: u32,
+	}
+
+	impl Voting {
+		#[ink(constructor)]
+		pub fn new(descriptions: Vec<String>) -> Self {
+			let mut voting = Self {
+				proposals: StorageHashMap::default(),
+				votes_received: StorageHashMap::default(),
+				next_proposal_id: 0,
+				admin: Self::env().caller(),
+			};
+			for description in descriptions.into_iter() {
+				voting
+					.insert_proposal(description)
+					.expect("too many proposals");
+			}
+			voting
+		}
+
+		// 添加提案, 仅管理员可调用, 返回新提案的id
+		#[ink(message)]
+		pub fn add_proposal(&mut self, description: String) -> Result<u32> {
+			if self.env().caller() != self.admin {
+				return Err(VotingError::Unauthorized);
+			}
+			let id = self.insert_proposal(description)?;
+			self.env().emit_event(ProposalAdded { proposal_id: id });
+			Ok(id)
+		}
+
+		// 获取提案数量
+		#[ink(message)]
+		pub fn get_proposals_len(&self) -> u32 {
+			self.next_proposal_id
+		}
+
+		// 获取某个提案
+		#[ink(message)]
+		pub fn get_proposal(&self, id: u32) -> Option<Proposal> {
+			self.proposals.get(&id).cloned()
+		}
+
+		// 获取全部提案, 按id从小到大排列
+		#[ink(message)]
+		pub fn get_proposals(&self) -> Vec<Proposal> {
+			(0..self.next_proposal_id)
+				.filter_map(|id| self.proposals.get(&id).cloned())
+				.collect()
+		}
+
+		// 获取当前各提案的票数, 按id从小到大排列
+		#[ink(message)]
+		pub fn get_current_votes(&self) -> Vec<(u32, u32)> {
+			(0..self.next_proposal_id)
+				.map(|id| (id, self.my_value_or_zero(id)))
+				.collect()
+		}
+
+		// 投票
+		#[ink(message)]
+		pub fn vote_proposal(&mut self, id: u32) -> Result<()> {
+			self.vote_proposal_without_event(id)?;
+			self.env().emit_event(VoteEvent {
+				from: self.env().caller(),
+				proposal_id: id,
+			});
+			Ok(())
+		}
+
+		fn vote_proposal_without_event(&mut self, id: u32) -> Result<()> {
+			if !self.proposals.contains_key(&id) {
+				return Err(VotingError::ProposalNotFound);
+			}
+			self.votes_received
+				.entry(id)
+				.and_modify(|v| *v += 1)
+				.or_insert(1);
+			Ok(())
+		}
+
+		// 获取某个提案的票数
+		#[ink(message)]
+		pub fn total_votes_for(&self, id: u32) -> u32 {
+			self.my_value_or_zero(id)
+		}
+
+		// 获取当前得票最多的提案id, 平票时返回id较小的提案
+		// 还没有人投票时返回None
+		#[ink(message)]
+		pub fn get_winner(&self) -> Option<u32> {
+			let mut winner = None;
+			let mut max_votes = 0;
+			for id in 0..self.next_proposal_id {
+				let votes = self.my_value_or_zero(id);
+				if votes > max_votes {
+					max_votes = votes;
+					winner = Some(id);
+				}
+			}
+			winner
+		}
+
+		// 内部辅助函数分配id并保存提案
+		fn insert_proposal(&mut self, description: String) -> Result<u32> {
+			let id = self.next_proposal_id;
+			self.next_proposal_id = id.checked_add(1).ok_or(VotingError::Overflow)?;
+			self.proposals.insert(id, Proposal { id, description });
+			Ok(id)
+		}
+
+		// 内部辅助函数获取某提案的投票数量
+		fn my_value_or_zero(&self, id: u32) -> u32 {
+			*self.votes_received.get(&id).unwrap_or(&0)
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use ink_env::test;
+		type Accounts = test::DefaultAccounts<Environment>;
+		fn default_accounts() -> Accounts {
+			test::default_accounts().expect("Test environment is expected to be initialized.")
+		}
+		fn set_caller(caller: AccountId) {
+			let callee =
+				ink_env::account_id::<ink_env::DefaultEnvironment>().unwrap_or([0x0; 32].into());
+			test::push_execution_context::<Environment>(
+				caller,
+				callee,
+				1000000,
+				1000000,
+				test::CallData::new(ink_env::call::Selector::new([0x00; 4])),
+			);
+		}
+		fn descriptions() -> Vec<String> {
+			ink_prelude::vec![String::from("first"), String::from("second")]
+		}
+
+		#[test]
+		fn default_works() {
+			let voting = Voting::new(Vec::new());
+			assert_eq!(voting.get_proposals_len(), 0);
+			assert_eq!(voting.get_proposals(), Vec::new());
+			assert_eq!(voting.get_winner(), None);
+		}
+
+		#[test]
+		fn init_proposals() {
+			let voting = Voting::new(descriptions());
+			assert_eq!(voting.get_proposals_len(), 2);
+			assert_eq!(
+				voting.get_proposal(0),
+				Some(Proposal {
+					id: 0,
+					description: String::from("first")
+				})
+			);
+			assert_eq!(
+				voting.get_proposal(1),
+				Some(Proposal {
+					id: 1,
+					description: String::from("second")
+				})
+			);
+			assert_eq!(voting.get_proposal(2), None);
+		}
+
+		#[test]
+		fn add_proposal_works() {
+			let mut voting = Voting::new(descriptions());
+			assert_eq!(voting.add_proposal(String::from("third")), Ok(2));
+			assert_eq!(voting.add_proposal(String::from("fourth")), Ok(3));
+			assert_eq!(voting.get_proposals_len(), 4);
+			let proposals = voting.get_proposals();
+			assert_eq!(proposals.len(), 4);
+			assert_eq!(proposals[2].id, 2);
+			assert_eq!(proposals[2].description, String::from("third"));
+		}
+
+		#[test]
+		fn add_proposal_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let mut voting = Voting::new(descriptions());
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.add_proposal(String::from("third")).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_proposals_len(), 2);
+		}
+
+		#[test]
+		fn vote_works() {
+			let mut voting = Voting::new(descriptions());
+			assert_eq!(voting.total_votes_for(0), 0);
+			voting.vote_proposal_without_event(1).unwrap();
+			voting.vote_proposal_without_event(1).unwrap();
+			voting.vote_proposal_without_event(0).unwrap();
+			assert_eq!(voting.total_votes_for(0), 1);
+			assert_eq!(voting.total_votes_for(1), 2);
+			assert_eq!(
+				voting.get_current_votes(),
+				ink_prelude::vec![(0, 1), (1, 2)]
+			);
+		}
+
+		#[test]
+		fn vote_invalid_proposal_does_not_work() {
+			let mut voting = Voting::new(descriptions());
+			assert_eq!(
+				voting.vote_proposal_without_event(2).unwrap_err(),
+				VotingError::ProposalNotFound
+			);
+			assert_eq!(voting.total_votes_for(2), 0);
+		}
+
+		#[test]
+		fn get_winner_works() {
+			let mut voting = Voting::new(descriptions());
+			voting.vote_proposal_without_event(0).unwrap();
+			voting.vote_proposal_without_event(1).unwrap();
+			// 平票时返回id较小的提案
+			assert_eq!(voting.get_winner(), Some(0));
+			voting.vote_proposal_without_event(1).unwrap();
+			assert_eq!(voting.get_winner(), Some(1));
+		}
+	}
+}
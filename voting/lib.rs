@@ -4,46 +4,105 @@ use ink_lang as ink;
 
 #[ink::contract]
 mod voting {
-	use ink_prelude::vec::Vec;
+	use ink_prelude::{string::String, vec::Vec};
 	use ink_storage::collections::{HashMap as StorageHashMap, Vec as StorageVec};
+	use voting_core::VotingCore;
 	// 定义持久化变量
 	// votes_received: 每个用户获取的投票数量
 	// candidate_list: 可被投票的用户列表
-	// in_candidate_list: 冗余信息用于快速判断某个用户是否在可投票列表中
+	// in_candidate_list: 冗余信息用于快速判断某个用户是否在可投票列表中, 包含不信任票
+	// one_vote_per_voter: 是否限制每个用户只能投一票
+	// has_voted: 已经投过票的用户
+	// comments: 投票人给某候选人投票时留下的理由
 	#[ink(storage)]
 	pub struct Voting {
 		votes_received: StorageHashMap<AccountId, u32>,
 		candidate_list: StorageVec<AccountId>,
 		in_candidate_list: StorageHashMap<AccountId, ()>,
+		one_vote_per_voter: bool,
+		has_voted: StorageHashMap<AccountId, ()>,
+		comments: StorageHashMap<(AccountId, AccountId), String>,
 	}
 
+	// 投票相关操作的错误类型
+	#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+	pub enum VotingError {
+		// 被投票人不在候选人列表中
+		NotACandidate,
+		// 该用户已经投过票
+		AlreadyVoted,
+		// 投票理由超过长度上限
+		CommentTooLong,
+		// 候选人列表中有重复的用户
+		DuplicateCandidate,
+		// 候选人使用了不信任票的保留账户
+		ReservedCandidate,
+	}
+
+	pub type Result<T> = core::result::Result<T, VotingError>;
+
+	// 投票理由的最大字节数
+	pub const MAX_COMMENT_LEN: usize = 256;
+
+	// 不信任票对应的保留账户, 投给它表示不信任所有候选人
+	pub const NO_CONFIDENCE_ID: [u8; 32] = [0xFF; 32];
+
 	#[derive(scale::Encode, scale::Decode)]
 	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 	pub struct CurrentVote {
 		candidate_list: Vec<AccountId>,
 		current_vote: Vec<u32>,
 	}
-	// 投票触发事件定义
+	// 投票触发事件定义, 带上投票后的状态方便链下索引
+	// 基础投票合约每次只投一票, 没有剩余票数
+	// candidate_total: 候选人投票后的总票数
 	#[ink(event)]
-	pub struct VoteEvent {
+	pub struct VoteCast {
 		#[ink(topic)]
 		from: AccountId,
 		#[ink(topic)]
 		to: AccountId,
+		amount: u32,
+		candidate_total: u32,
+		block_number: BlockNumber,
 	}
 
 	impl Voting {
 		#[ink(constructor)]
-		pub fn new(lists: Vec<AccountId>) -> Self {
-			let in_candidate_list: StorageHashMap<_, _, _> =
+		pub fn new(lists: Vec<AccountId>, one_vote_per_voter: bool) -> Self {
+			match Self::try_new(lists, one_vote_per_voter) {
+				Ok(voting) => voting,
+				Err(VotingError::ReservedCandidate) => {
+					panic!("NO_CONFIDENCE_ID is reserved and cannot be a candidate")
+				}
+				Err(_) => panic!("invalid candidate list"),
+			}
+		}
+
+		// 检查参数后创建合约, 参数不合法时返回错误
+		// ink!的构造函数只能返回Self, 所以new遇到错误时仍然会panic
+		pub fn try_new(lists: Vec<AccountId>, one_vote_per_voter: bool) -> Result<Self> {
+			let no_confidence = AccountId::from(NO_CONFIDENCE_ID);
+			if lists.contains(&no_confidence) {
+				return Err(VotingError::ReservedCandidate);
+			}
+			let mut in_candidate_list: StorageHashMap<_, _, _> =
 				lists.iter().copied().map(|x| (x, ())).collect();
 			let candidate_list: StorageVec<_> = lists.iter().copied().collect();
-			assert!(in_candidate_list.len() == candidate_list.len());
-			Self {
+			if in_candidate_list.len() != candidate_list.len() {
+				return Err(VotingError::DuplicateCandidate);
+			}
+			// 不信任票可以被投票, 但不在候选人列表中
+			in_candidate_list.insert(no_confidence, ());
+			Ok(Self {
 				candidate_list,
 				votes_received: StorageHashMap::default(),
 				in_candidate_list,
-			}
+				one_vote_per_voter,
+				has_voted: StorageHashMap::default(),
+				comments: StorageHashMap::default(),
+			})
 		}
 
 		// 获取可被投票的用户数量
@@ -52,6 +111,13 @@ mod voting {
 			self.candidate_list.len()
 		}
 
+		// 某用户是否在候选人列表中, 不信任票不算候选人
+		#[ink(message)]
+		pub fn is_candidate(&self, account: AccountId) -> bool {
+			account != AccountId::from(NO_CONFIDENCE_ID)
+				&& self.in_candidate_list.contains_key(&account)
+		}
+
 		// 获取可被投票的用户
 		#[ink(message)]
 		pub fn get_candidates(&mut self) -> Vec<AccountId> {
@@ -59,7 +125,7 @@ mod voting {
 			lists
 		}
 
-		// 获取当前各用户投票票数状态
+		// 获取当前各用户投票票数状态, 不信任票通过no_confidence_votes查询
 		#[ink(message)]
 		pub fn get_current_votes(&mut self) -> CurrentVote {
 			let candidate_list: Vec<_> = self.candidate_list.iter().copied().collect();
@@ -75,28 +141,59 @@ mod voting {
 
 		// 投票
 		#[ink(message)]
-		pub fn vote_candidate(&mut self, candidate: AccountId) -> bool {
-			let ret: bool = self.vote_candidate_without_event(candidate);
-			if ret {
-				self.env().emit_event(VoteEvent {
-					from: self.env().caller(),
-					to: candidate,
-				});
-			}
-			ret
+		pub fn vote_candidate(&mut self, candidate: AccountId) -> Result<()> {
+			self.vote_candidate_without_event(candidate)?;
+			self.env().emit_event(VoteCast {
+				from: self.env().caller(),
+				to: candidate,
+				amount: 1,
+				candidate_total: self.my_value_or_zero(candidate),
+				block_number: self.env().block_number(),
+			});
+			Ok(())
 		}
 
 		// it seems unit test failed when emit event if call vote_candidate function directly
-		fn vote_candidate_without_event(&mut self, candidate: AccountId) -> bool {
+		fn vote_candidate_without_event(&mut self, candidate: AccountId) -> Result<()> {
 			if !self.in_candidate_list.contains_key(&candidate) {
-				return false;
+				return Err(VotingError::NotACandidate);
 			}
-			self
-				.votes_received
+			if self.one_vote_per_voter {
+				let caller = self.env().caller();
+				if self.has_voted.contains_key(&caller) {
+					return Err(VotingError::AlreadyVoted);
+				}
+				self.has_voted.insert(caller, ());
+			}
+			self.votes_received
 				.entry(candidate)
 				.and_modify(|v| *v += 1)
 				.or_insert(1);
-			true
+			Ok(())
+		}
+
+		// 投票并留下理由, 再次给同一个候选人投票时会覆盖之前的理由
+		#[ink(message)]
+		pub fn vote_with_comment(&mut self, candidate: AccountId, comment: String) -> Result<()> {
+			if comment.len() > MAX_COMMENT_LEN {
+				return Err(VotingError::CommentTooLong);
+			}
+			self.vote_candidate(candidate)?;
+			let caller = self.env().caller();
+			self.comments.insert((caller, candidate), comment);
+			Ok(())
+		}
+
+		// 获取某投票人给某候选人投票时留下的理由
+		#[ink(message)]
+		pub fn get_comment(&self, voter: AccountId, candidate: AccountId) -> Option<String> {
+			self.comments.get(&(voter, candidate)).cloned()
+		}
+
+		// 某用户是否已经投过票, 只在限制每人一票时记录
+		#[ink(message)]
+		pub fn has_voter_voted(&self, voter: AccountId) -> bool {
+			self.has_voted.contains_key(&voter)
 		}
 
 		// 获取某用户被投票的数量
@@ -105,6 +202,34 @@ mod voting {
 			self.my_value_or_zero(candidate)
 		}
 
+		// 获取不信任票的数量
+		#[ink(message)]
+		pub fn no_confidence_votes(&self) -> u32 {
+			self.my_value_or_zero(AccountId::from(NO_CONFIDENCE_ID))
+		}
+
+		// 获取当前得票最多的候选人, 平票时返回在candidate_list中靠前的候选人
+		// 还没有人得票或不信任票超过总票数一半时返回None
+		#[ink(message)]
+		pub fn get_winner(&self) -> Option<AccountId> {
+			let no_confidence = self.no_confidence_votes();
+			let mut total = no_confidence;
+			let mut winner = None;
+			let mut max_votes = 0;
+			for x in self.candidate_list.iter() {
+				let votes = self.my_value_or_zero(*x);
+				total = total.saturating_add(votes);
+				if votes > max_votes {
+					max_votes = votes;
+					winner = Some(*x);
+				}
+			}
+			if no_confidence > total / 2 {
+				return None;
+			}
+			winner
+		}
+
 		// 内部辅助函数用户确认某用户是否被存在candidate_list中
 		fn valid_candidate(&self, candidate: AccountId) -> bool {
 			for x in self.candidate_list.into_iter() {
@@ -122,6 +247,35 @@ mod voting {
 		}
 	}
 
+	impl VotingCore for Voting {
+		#[ink(message)]
+		fn total_votes_for(&self, candidate: AccountId) -> u128 {
+			self.my_value_or_zero(candidate).into()
+		}
+
+		#[ink(message)]
+		fn get_candidates(&self) -> Vec<AccountId> {
+			self.candidate_list.iter().copied().collect()
+		}
+
+		// 基础投票合约每次只能投一票, amount必须为1
+		#[ink(message)]
+		fn vote_candidate(&mut self, candidate: AccountId, amount: u128) -> bool {
+			if amount != 1 {
+				return false;
+			}
+			Voting::vote_candidate(self, candidate).is_ok()
+		}
+
+		#[ink(message)]
+		fn get_current_votes(&self) -> Vec<(AccountId, u128)> {
+			self.candidate_list
+				.iter()
+				.map(|x| (*x, self.my_value_or_zero(*x).into()))
+				.collect()
+		}
+	}
+
 	#[cfg(test)]
 	mod tests {
 		use super::*;
@@ -134,7 +288,7 @@ mod voting {
 		}
 		#[test]
 		fn default_works() {
-			let mut voting = Voting::new(Vec::new());
+			let mut voting = Voting::new(Vec::new(), false);
 			assert_eq!(voting.candidate_list, StorageVec::new());
 			assert_eq!(voting.candidate_list.len(), 0);
 			assert_eq!(voting.get_candidates_len(), 0);
@@ -144,52 +298,315 @@ mod voting {
 		fn init_candidates() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
-			let voting = Voting::new(candidates);
+			let voting = Voting::new(candidates, false);
 			assert_eq!(voting.candidate_list.len(), 3);
 		}
 
+		#[test]
+		fn init_duplicate_candidates_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.alice];
+			assert_eq!(
+				Voting::try_new(candidates, false).err(),
+				Some(VotingError::DuplicateCandidate)
+			);
+			// 不信任票的保留账户不能作为候选人
+			let candidates = ink_prelude::vec![accounts.alice, AccountId::from(NO_CONFIDENCE_ID)];
+			assert_eq!(
+				Voting::try_new(candidates, false).err(),
+				Some(VotingError::ReservedCandidate)
+			);
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			assert!(Voting::try_new(candidates, false).is_ok());
+		}
+
+		#[test]
+		#[should_panic(expected = "NO_CONFIDENCE_ID is reserved and cannot be a candidate")]
+		fn new_with_reserved_candidate_panics() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, AccountId::from(NO_CONFIDENCE_ID)];
+			Voting::new(candidates, false);
+		}
+
+		#[test]
+		fn is_candidate_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let voting = Voting::new(candidates, false);
+			assert!(voting.is_candidate(accounts.alice));
+			assert!(voting.is_candidate(accounts.bob));
+			assert!(!voting.is_candidate(accounts.eve));
+			let voting = Voting::new(Vec::new(), false);
+			assert!(!voting.is_candidate(accounts.alice));
+		}
+
 		#[test]
 		fn vote_works() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
-			let mut voting = Voting::new(candidates);
+			let mut voting = Voting::new(candidates, false);
 			assert_eq!(voting.total_votes_for(accounts.alice), 0);
 			assert_eq!(voting.total_votes_for(accounts.bob), 0);
 			assert_eq!(voting.total_votes_for(accounts.eve), 0);
-			voting.vote_candidate_without_event(accounts.alice);
+			voting.vote_candidate_without_event(accounts.alice).unwrap();
 			assert_eq!(voting.total_votes_for(accounts.alice), 1);
-			voting.vote_candidate_without_event(accounts.alice);
+			voting.vote_candidate_without_event(accounts.alice).unwrap();
 			assert_eq!(voting.total_votes_for(accounts.alice), 2);
 			assert_eq!(voting.total_votes_for(accounts.bob), 0);
 			assert_eq!(voting.total_votes_for(accounts.eve), 0);
-			voting.vote_candidate_without_event(accounts.bob);
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
 			assert_eq!(voting.total_votes_for(accounts.alice), 2);
 			assert_eq!(voting.total_votes_for(accounts.bob), 1);
 			assert_eq!(voting.total_votes_for(accounts.eve), 0);
 		}
 
+		#[test]
+		fn one_vote_per_voter_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, true);
+			assert!(!voting.has_voter_voted(accounts.alice));
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+			assert!(voting.has_voter_voted(accounts.alice));
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.eve)
+					.unwrap_err(),
+				VotingError::AlreadyVoted
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+			assert_eq!(voting.total_votes_for(accounts.eve), 0);
+		}
+
+		#[test]
+		fn invalid_vote_is_not_recorded_as_voted() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, true);
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.eve)
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert!(!voting.has_voter_voted(accounts.alice));
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+		}
+
+		#[test]
+		fn multiple_votes_allowed_without_flag() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, false);
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 2);
+			assert!(!voting.has_voter_voted(accounts.alice));
+		}
+
+		#[test]
+		fn vote_with_comment_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, false);
+			assert_eq!(voting.get_comment(accounts.alice, accounts.bob), None);
+			voting
+				.vote_with_comment(accounts.bob, String::from("first"))
+				.unwrap();
+			assert_eq!(
+				voting.get_comment(accounts.alice, accounts.bob),
+				Some(String::from("first"))
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+			voting
+				.vote_with_comment(accounts.bob, String::from("second"))
+				.unwrap();
+			assert_eq!(
+				voting.get_comment(accounts.alice, accounts.bob),
+				Some(String::from("second"))
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 2);
+			assert_eq!(voting.get_comment(accounts.alice, accounts.alice), None);
+		}
+
+		#[test]
+		fn vote_with_long_comment_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, false);
+			let comment: String = core::iter::repeat('a').take(MAX_COMMENT_LEN).collect();
+			voting.vote_with_comment(accounts.bob, comment).unwrap();
+			let comment: String = core::iter::repeat('a').take(MAX_COMMENT_LEN + 1).collect();
+			assert_eq!(
+				voting.vote_with_comment(accounts.bob, comment).unwrap_err(),
+				VotingError::CommentTooLong
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+		}
+
+		#[test]
+		fn vote_with_comment_for_invalid_candidate_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, false);
+			assert_eq!(
+				voting
+					.vote_with_comment(accounts.eve, String::from("nope"))
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert_eq!(voting.get_comment(accounts.alice, accounts.eve), None);
+		}
+
+		#[test]
+		fn vote_candidate_emits_vote_cast() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, false);
+			voting.vote_candidate(accounts.bob).unwrap();
+			voting.vote_candidate(accounts.bob).unwrap();
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			assert_eq!(emitted_events.len(), 2);
+			let decoded = <Event as scale::Decode>::decode(&mut &emitted_events[1].data[..])
+				.expect("encountered invalid contract event data buffer");
+			if let Event::VoteCast(VoteCast {
+				from,
+				to,
+				amount,
+				candidate_total,
+				block_number,
+			}) = decoded
+			{
+				assert_eq!(from, accounts.alice);
+				assert_eq!(to, accounts.bob);
+				assert_eq!(amount, 1);
+				assert_eq!(candidate_total, 2);
+				assert_eq!(
+					block_number,
+					ink_env::block_number::<Environment>().unwrap()
+				);
+			} else {
+				panic!("encountered unexpected event kind: expected a VoteCast event")
+			}
+		}
+
 		#[test]
 		fn vote_invalid_candidate_does_not_work() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
-			let mut voting = Voting::new(candidates);
+			let mut voting = Voting::new(candidates, false);
 			assert_eq!(voting.total_votes_for(accounts.alice), 0);
 			assert_eq!(voting.total_votes_for(accounts.bob), 0);
-			assert_eq!(voting.vote_candidate(accounts.eve), false);
+			assert_eq!(
+				voting.vote_candidate(accounts.eve).unwrap_err(),
+				VotingError::NotACandidate
+			);
 		}
 
 		#[test]
 		fn get_current_votes_works() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
-			let mut voting = Voting::new(candidates.clone());
+			let mut voting = Voting::new(candidates.clone(), false);
 			let current = voting.get_current_votes();
 			assert_eq!(current.candidate_list, candidates.clone());
 			assert_eq!(current.current_vote, ink_prelude::vec![0, 0, 0]);
-			voting.vote_candidate_without_event(accounts.alice);
+			voting.vote_candidate_without_event(accounts.alice).unwrap();
 			let current = voting.get_current_votes();
 			assert_eq!(current.candidate_list, candidates.clone());
 			assert_eq!(current.current_vote, ink_prelude::vec![1, 0, 0]);
 		}
+
+		#[test]
+		fn no_confidence_is_not_a_listed_candidate() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates.clone(), false);
+			let no_confidence = AccountId::from(NO_CONFIDENCE_ID);
+			assert!(!voting.is_candidate(no_confidence));
+			assert_eq!(voting.get_candidates(), candidates);
+			assert_eq!(voting.get_candidates_len(), 2);
+			voting.vote_candidate_without_event(no_confidence).unwrap();
+			assert_eq!(voting.no_confidence_votes(), 1);
+			// 不信任票不出现在get_current_votes中
+			let current = voting.get_current_votes();
+			assert_eq!(current.candidate_list, candidates);
+			assert_eq!(current.current_vote, ink_prelude::vec![0, 0]);
+			// 不信任票本身不能成为获胜者
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+			assert_eq!(voting.get_winner(), Some(accounts.bob));
+		}
+
+		#[test]
+		fn get_winner_with_no_confidence_majority_is_none() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, false);
+			let no_confidence = AccountId::from(NO_CONFIDENCE_ID);
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+			voting.vote_candidate_without_event(accounts.alice).unwrap();
+			voting.vote_candidate_without_event(no_confidence).unwrap();
+			voting.vote_candidate_without_event(no_confidence).unwrap();
+			// 不信任票正好占一半, 还没有超过
+			assert_eq!(voting.get_winner(), Some(accounts.alice));
+			voting.vote_candidate_without_event(no_confidence).unwrap();
+			assert_eq!(voting.no_confidence_votes(), 3);
+			assert_eq!(voting.get_winner(), None);
+		}
+
+		#[test]
+		fn voting_core_trait_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates.clone(), false);
+			assert_eq!(VotingCore::get_candidates(&voting), candidates);
+			assert!(VotingCore::vote_candidate(&mut voting, accounts.bob, 1));
+			assert!(!VotingCore::vote_candidate(&mut voting, accounts.bob, 2));
+			assert!(!VotingCore::vote_candidate(&mut voting, accounts.eve, 1));
+			assert_eq!(VotingCore::total_votes_for(&voting, accounts.bob), 1);
+			assert_eq!(
+				VotingCore::get_current_votes(&voting),
+				ink_prelude::vec![(accounts.alice, 0), (accounts.bob, 1)]
+			);
+		}
+
+		#[test]
+		fn get_winner_without_votes_is_none() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let voting = Voting::new(candidates, false);
+			assert_eq!(voting.get_winner(), None);
+		}
+
+		#[test]
+		fn get_winner_single_candidate() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.bob];
+			let mut voting = Voting::new(candidates, false);
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+			assert_eq!(voting.get_winner(), Some(accounts.bob));
+		}
+
+		#[test]
+		fn get_winner_tie_returns_earliest_candidate() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, false);
+			voting.vote_candidate_without_event(accounts.eve).unwrap();
+			voting.vote_candidate_without_event(accounts.bob).unwrap();
+			assert_eq!(voting.get_winner(), Some(accounts.bob));
+		}
+
+		#[test]
+		fn get_winner_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, false);
+			voting.vote_candidate_without_event(accounts.alice).unwrap();
+			voting.vote_candidate_without_event(accounts.eve).unwrap();
+			voting.vote_candidate_without_event(accounts.eve).unwrap();
+			assert_eq!(voting.get_winner(), Some(accounts.eve));
+		}
 	}
 }
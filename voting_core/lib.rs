@@ -0,0 +1,34 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_env::AccountId;
+use ink_lang as ink;
+use ink_prelude::vec::Vec;
+
+// 两个投票合约共用的接口, 其他合约可以通过该trait跨合约调用
+// 票数统一使用u128
+#[ink::trait_definition]
+pub trait VotingCore {
+	// 获取某用户被投票的数量
+	#[ink(message)]
+	fn total_votes_for(&self, candidate: AccountId) -> u128;
+
+	// 获取可被投票的用户
+	#[ink(message)]
+	fn get_candidates(&self) -> Vec<AccountId>;
+
+	// 调用者给candidate投amount票, 成功时返回true
+	#[ink(message)]
+	fn vote_candidate(&mut self, candidate: AccountId, amount: u128) -> bool;
+
+	// 获取当前各用户投票票数状态
+	#[ink(message)]
+	fn get_current_votes(&self) -> Vec<(AccountId, u128)>;
+}
+
+// 代理投票接口, 由登记过的代理合约代替用户投票
+#[ink::trait_definition]
+pub trait VoteProxy {
+	// 代理合约用owner手中的票给candidate投amount票, 成功时返回true
+	#[ink(message)]
+	fn proxy_vote(&mut self, owner: AccountId, candidate: AccountId, amount: u128) -> bool;
+}
@@ -4,20 +4,448 @@ use ink_lang as ink;
 
 #[ink::contract]
 mod voting {
-	use ink_prelude::vec::Vec;
+	use ink_prelude::{string::String, vec::Vec};
 	use ink_storage::{
 		collections::{HashMap as StorageHashMap, Vec as StorageVec},
 		traits::{PackedLayout, SpreadLayout},
 	};
+	use voting_core::{VoteProxy, VotingCore};
 
-	#[derive(scale::Encode, scale::Decode)]
+	// 投票相关操作的错误类型
+	#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+	pub enum VotingError {
+		// 被投票人不在候选人列表中
+		NotACandidate,
+		// 候选人已存在
+		CandidateAlreadyExists,
+		// 投票人手中票数不足
+		InsufficientTickets,
+		// 剩余票数不足
+		SoldOut,
+		// 调用者无权限
+		Unauthorized,
+		// 投票已截止
+		VotingEnded,
+		// 数量不能为0
+		ZeroAmount,
+		// 数值溢出
+		Overflow,
+		// 投票人不在白名单中
+		NotWhitelisted,
+		// 合约已暂停
+		ContractPaused,
+		// 已投出的票数不足
+		InsufficientVotes,
+		// 投票人已将投票权委托给他人
+		AlreadyDelegated,
+		// 委托关系形成环
+		CircularDelegation,
+		// 当前阶段不允许该操作
+		WrongPhase,
+		// 快照数量已达上限
+		SnapshotLimitReached,
+		// 票价低于最低价格
+		PriceBelowMinimum,
+		// 候选人得票数超过上限
+		CandidateCapExceeded,
+		// 总票数超过上限
+		MaxSupplyExceeded,
+		// 投票人投给某候选人的票数超过上限
+		ExceedsVoterCap,
+		// 排序投票为空或有重复的候选人
+		InvalidBallot,
+		// 已经有人投过票
+		VotesAlreadyCast,
+		// 投票人买的票数超过上限
+		ExceedsTicketCap,
+		// 按时间加权计票时不能撤回投票
+		RevokeNotSupported,
+		// 合约已被紧急停止
+		ContractStopped,
+		// 候选人列表已锁定
+		CandidateListLocked,
+		// 元数据过长
+		MetadataTooLong,
+		// 按代币持有量计票时投票人没有代币
+		NoTokenBalance,
+		// 调用代币合约失败
+		TokenCallFailed,
+		// 投票还没有过期
+		VoteNotExpired,
+		// 投票人没有提交投票承诺, 或已经揭示过
+		NoCommitment,
+		// 揭示的投票与承诺不符
+		CommitmentMismatch,
+		// 候选人别名过长
+		AliasTooLong,
+		// 候选人列表中有重复的用户
+		DuplicateCandidate,
+		// 已经否决过该候选人
+		AlreadyVetoed,
+		// 买票时转入的金额不足
+		InsufficientPayment,
+		// 代理投票额度不足
+		InsufficientAllowance,
+		// 没有开启信念投票
+		ConvictionDisabled,
+		// 数据检查: in_candidate_list与candidate_list的长度不一致
+		CandidateIndexMismatch,
+		// 数据检查: 剩余票数超过总票数
+		TicketPoolExceedsTotal,
+		// 数据检查: 投票人手中的票, 已花费的票, 弃权票, 待领回的票和剩余票数之和不等于总票数
+		TicketSupplyMismatch,
+		// 数据检查: 投票记录中的候选人不在候选人列表中
+		OrphanedVote,
+		// 没有设置质押代币合约
+		StakingDisabled,
+		// 手中还有没投出的票, 不能取回质押
+		UncastTickets,
+		// 质押换到的票只能投出, 不能转让, 委托或退回
+		StakedTicketsLocked,
+		// 手续费比例超过10000
+		InvalidFee,
+		// 多签操作不存在
+		ActionNotFound,
+		// 已经批准过该多签操作
+		AlreadyApproved,
+		// 多签操作的批准人数不足
+		NotEnoughApprovals,
+		// 多签操作的函数选择器不受支持
+		UnknownAction,
+		// 多签操作的参数无法解码
+		InvalidActionArgs,
+		// 管理员列表为空, 有重复, 或批准人数不在1到管理员人数之间
+		InvalidApprovalThreshold,
+		// 状态快照中候选人和票数的数量不一致
+		InvalidSnapshot,
+		// 得票占比上限超过10000
+		InvalidVoteShare,
+		// 投票后候选人的得票占比超过上限
+		VoteShareExceeded,
+		// 没有设置闪电投票的回调合约
+		FlashHookNotSet,
+		// 闪电投票的回调合约调用失败或拒绝
+		FlashHookFailed,
+		// 没有设置代币合约, 不能锁定代币
+		TokenWeightDisabled,
+		// 设置了代币合约时token_weight_divisor和max_token_weight不能为0
+		InvalidTokenWeight,
+	}
+
+	pub type Result<T> = core::result::Result<T, VotingError>;
+
+	// 选举元数据的最大字节数
+	pub const MAX_METADATA_LEN: usize = 512;
+
+	// 候选人别名的最大字节数
+	pub const MAX_ALIAS_LEN: usize = 64;
+
+	// 未设置max_history_blocks时得票速度记录保留的区块数
+	pub const DEFAULT_VELOCITY_BLOCKS: u32 = 1_000;
+
+	// 定点数log2结果中小数部分的位数
+	const LOG2_FRACTION_BITS: u32 = 32;
+
+	// PSP22::transfer的选择器
+	pub const PSP22_TRANSFER_SELECTOR: [u8; 4] = [0xDB, 0x20, 0xF9, 0xF5];
+
+	// PSP22::transfer_from的选择器
+	pub const PSP22_TRANSFER_FROM_SELECTOR: [u8; 4] = [0x54, 0xB3, 0xC7, 0x6E];
+
+	// 闪电投票回调合约invoke_flash_hook的选择器
+	pub const FLASH_HOOK_SELECTOR: [u8; 4] = [0xD6, 0x1F, 0x1B, 0x19];
+
+	// PSP22代币合约返回的错误类型, 只用于解码跨合约调用的返回值
+	#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+	pub enum PSP22Error {
+		Custom(String),
+		InsufficientBalance,
+		InsufficientAllowance,
+		ZeroRecipientAddress,
+		ZeroSenderAddress,
+		SafeTransferCheckFailed(String),
+	}
+
+	// 多签操作支持的函数选择器, 参数为对应消息参数的SCALE编码
+	// ACTION_ADD_CANDIDATE: add_candidate(AccountId)
+	// ACTION_REMOVE_CANDIDATE: remove_candidate(AccountId)
+	// ACTION_ADVANCE_PHASE: advance_phase()
+	// ACTION_PAUSE: pause()
+	// ACTION_UNPAUSE: unpause()
+	// ACTION_SET_ADMINS: set_admins(Vec<AccountId>, u32)
+	// ACTION_BATCH_ADD_CANDIDATES: batch_add_candidates(Vec<AccountId>)
+	// ACTION_LOCK_CANDIDATE_LIST: lock_candidate_list()
+	// ACTION_TRANSFER_ADMIN: (原管理员, 新管理员), 把原管理员的身份转给新管理员, 原管理员必须在管理员列表中
+	// ACTION_OVERRIDE_VOTE_COUNT: override_vote_count(AccountId, u128)
+	// ACTION_RESET_ELECTION: reset_election()
+	// ACTION_IMPORT_PREVIOUS_VOTES: import_previous_votes(Vec<VoteOfCandidate>)
+	// ACTION_SET_VOTER_WEIGHT: set_voter_weight(AccountId, u128)
+	// ACTION_EMERGENCY_STOP: emergency_stop()
+	// ACTION_SET_PROXY_CONTRACT: set_proxy_contract(Option<AccountId>)
+	// ACTION_SET_METADATA: set_metadata(String)
+	// ACTION_SET_MERKLE_ROOT: set_merkle_root([u8; 32])
+	// ACTION_SET_WHITELIST_ENABLED: set_whitelist_enabled(bool)
+	// ACTION_MINT_TOKENS: mint_tokens(u128)
+	// ACTION_SET_TOKEN_PRICE: set_token_price(u128)
+	// ACTION_IMPORT_STATE: import_state(StateSnapshot)
+	// ACTION_REGISTER_VOTER: register_voter(AccountId)
+	// ACTION_TAKE_SNAPSHOT: take_snapshot()
+	pub const ACTION_ADD_CANDIDATE: [u8; 4] = [0x00, 0x00, 0x00, 0x01];
+	pub const ACTION_REMOVE_CANDIDATE: [u8; 4] = [0x00, 0x00, 0x00, 0x02];
+	pub const ACTION_ADVANCE_PHASE: [u8; 4] = [0x00, 0x00, 0x00, 0x03];
+	pub const ACTION_PAUSE: [u8; 4] = [0x00, 0x00, 0x00, 0x04];
+	pub const ACTION_UNPAUSE: [u8; 4] = [0x00, 0x00, 0x00, 0x05];
+	pub const ACTION_SET_ADMINS: [u8; 4] = [0x00, 0x00, 0x00, 0x06];
+	pub const ACTION_BATCH_ADD_CANDIDATES: [u8; 4] = [0x00, 0x00, 0x00, 0x07];
+	pub const ACTION_LOCK_CANDIDATE_LIST: [u8; 4] = [0x00, 0x00, 0x00, 0x08];
+	pub const ACTION_TRANSFER_ADMIN: [u8; 4] = [0x00, 0x00, 0x00, 0x09];
+	pub const ACTION_OVERRIDE_VOTE_COUNT: [u8; 4] = [0x00, 0x00, 0x00, 0x0a];
+	pub const ACTION_RESET_ELECTION: [u8; 4] = [0x00, 0x00, 0x00, 0x0b];
+	pub const ACTION_IMPORT_PREVIOUS_VOTES: [u8; 4] = [0x00, 0x00, 0x00, 0x0c];
+	pub const ACTION_SET_VOTER_WEIGHT: [u8; 4] = [0x00, 0x00, 0x00, 0x0d];
+	pub const ACTION_EMERGENCY_STOP: [u8; 4] = [0x00, 0x00, 0x00, 0x0e];
+	pub const ACTION_SET_PROXY_CONTRACT: [u8; 4] = [0x00, 0x00, 0x00, 0x0f];
+	pub const ACTION_SET_METADATA: [u8; 4] = [0x00, 0x00, 0x00, 0x10];
+	pub const ACTION_SET_MERKLE_ROOT: [u8; 4] = [0x00, 0x00, 0x00, 0x11];
+	pub const ACTION_SET_WHITELIST_ENABLED: [u8; 4] = [0x00, 0x00, 0x00, 0x12];
+	pub const ACTION_MINT_TOKENS: [u8; 4] = [0x00, 0x00, 0x00, 0x13];
+	pub const ACTION_SET_TOKEN_PRICE: [u8; 4] = [0x00, 0x00, 0x00, 0x14];
+	pub const ACTION_IMPORT_STATE: [u8; 4] = [0x00, 0x00, 0x00, 0x15];
+	pub const ACTION_REGISTER_VOTER: [u8; 4] = [0x00, 0x00, 0x00, 0x16];
+	pub const ACTION_TAKE_SNAPSHOT: [u8; 4] = [0x00, 0x00, 0x00, 0x17];
+
+	// 计票方式
+	// Linear: 投n票花费n张票
+	// Quadratic: 投给同一候选人的累计票数为n时共花费n²张票
+	#[derive(
+		Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout,
+	)]
+	#[cfg_attr(
+		feature = "std",
+		derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+	)]
+	pub enum VotingMode {
+		Linear,
+		Quadratic,
+	}
+
+	// 投票阶段, 由管理员依次推进
+	// Registration: 只能买票
+	// Active: 只能投票
+	// Ended: 投票结束, 可以查询获胜者
+	#[derive(
+		Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout,
+	)]
+	#[cfg_attr(
+		feature = "std",
+		derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+	)]
+	pub enum VotingPhase {
+		Registration,
+		Active,
+		Ended,
+	}
+
+	// 分配手中剩余票的策略, 平票时都选候选人列表中靠前的
+	// MaximizeLeader: 全部投给当前得票最多的候选人
+	// BoostUnderdog: 全部投给当前得票最少的候选人
+	// Spread: 平均分给所有候选人, 除不尽的部分从列表靠前的候选人开始每人多分一张
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+	pub enum AllocationStrategy {
+		MaximizeLeader,
+		BoostUnderdog,
+		Spread,
+	}
+
+	// 部署参数
+	// total_tokens: 总的票数
+	// max_supply: 增发后总票数的上限
+	// token_price: 每张票的价格
+	// min_price: 票价下限, 管理员修改票价时不能低于此值
+	// duration_ms: 投票持续时间(毫秒)
+	// mode: 计票方式
+	// enable_whitelist: 是否开启白名单
+	// merkle_root: 白名单默克尔树的根
+	// max_snapshots: 快照数量上限
+	// max_votes_per_candidate: 每个候选人最多能获得的票数
+	// seats: 选出的获胜者人数
+	// max_votes_per_voter_per_candidate: 每个投票人最多能投给同一个候选人的票数
+	// min_votes_threshold: 候选人进入结果所需的最低票数
+	// max_tickets_per_voter: 每个投票人最多能买的票数
+	// time_weighted: 是否按时间加权计票, 越早投的票计入越多
+	// metadata: 选举的元数据, 如IPFS CID或URL
+	// token_contract: PSP22代币合约, 设置后投票人的权重还要乘以其锁定代币换算的代币权重
+	// vote_expiry_blocks: 投票在多少个区块后过期, 为0时永不过期
+	// veto_threshold_bps: 否决人数超过已投票数的这个比例(万分之一)时候选人不能获胜, 为0时不启用
+	// quorum_votes: 选举有效所需的最低总票数, 不足时没有获胜者
+	// require_payment: 买票时是否需要转入等额的原生代币作为抵押
+	// max_history_blocks: 按区块记录的投票历史保留最近多少个区块, 为0时不记录
+	// auction_step: 每卖出多少张票票价上涨1, 为0时票价固定为token_price
+	// election_id: 选举编号, 写入每个事件, 方便链下按选举筛选事件
+	// conviction_period: 信念投票中票数倍数加1所需的区块数, 为0时不启用
+	// max_conviction_multiplier: 信念投票的最大倍数
+	// warning_window_ms: 距离截止时间少于这么多毫秒时提醒投票人还有未投出的票
+	// decay_rate_bps_per_block: 每过一个区块投票的有效权重按这个比例(万分之一)衰减, 为0时不衰减
+	// stake_token: 质押换票使用的PSP22代币合约, 为None时不能质押
+	// fee_bps: 每次投票扣除的手续费比例(万分之一), 扣除的票数计入fee_treasury, 为0时不收取
+	// fee_treasury: 收取手续费的账户
+	// flash_hook_contract: 闪电投票时调用的回调合约, 为None时不能闪电投票
+	// max_votes_share_bps: 每个候选人得票占候选人总票数的上限(万分之一), 为0时不限制
+	// min_votes_for_share_cap: 投票后总票数达到多少时才检查得票占比上限, 否则第一票总会超过上限
+	// token_weight_divisor: 每锁定多少个代币得到1点代币权重
+	// max_token_weight: 代币权重的上限
+	#[derive(Clone, scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, Debug, PartialEq, Eq))]
+	pub struct VotingConfig {
+		pub total_tokens: u128,
+		pub max_supply: u128,
+		pub token_price: u128,
+		pub min_price: u128,
+		pub duration_ms: u64,
+		pub mode: VotingMode,
+		pub enable_whitelist: bool,
+		pub merkle_root: [u8; 32],
+		pub max_snapshots: u32,
+		pub max_votes_per_candidate: u128,
+		pub seats: u32,
+		pub max_votes_per_voter_per_candidate: u128,
+		pub min_votes_threshold: u128,
+		pub max_tickets_per_voter: u128,
+		pub time_weighted: bool,
+		pub metadata: String,
+		pub token_contract: Option<AccountId>,
+		pub vote_expiry_blocks: u32,
+		pub veto_threshold_bps: u32,
+		pub quorum_votes: u128,
+		pub require_payment: bool,
+		pub max_history_blocks: u32,
+		pub auction_step: u128,
+		pub election_id: u32,
+		pub conviction_period: u32,
+		pub max_conviction_multiplier: u128,
+		pub warning_window_ms: u64,
+		pub decay_rate_bps_per_block: u32,
+		pub stake_token: Option<AccountId>,
+		pub fee_bps: u32,
+		pub fee_treasury: AccountId,
+		pub flash_hook_contract: Option<AccountId>,
+		pub max_votes_share_bps: u32,
+		pub min_votes_for_share_cap: u128,
+		pub token_weight_divisor: u128,
+		pub max_token_weight: u128,
+	}
+
+	#[derive(Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
 	#[cfg_attr(
 		feature = "std",
-		derive(scale_info::TypeInfo, Debug, SpreadLayout, PackedLayout, PartialEq, Eq,)
+		derive(
+			scale_info::TypeInfo,
+			ink_storage::traits::StorageLayout,
+			Debug,
+			PartialEq,
+			Eq,
+		)
 	)]
 	pub struct VoteOfCandidate {
 		candidate: AccountId,
-		vote: u32,
+		vote: u128,
+	}
+
+	// 选举概况, 一次查询返回所有主要统计数据
+	// winner: 投票结束前为None
+	#[derive(scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, Debug, PartialEq, Eq))]
+	pub struct ElectionSummary {
+		total_tokens: u128,
+		balance_tokens: u128,
+		candidate_count: u32,
+		votes: Vec<VoteOfCandidate>,
+		winner: Option<AccountId>,
+	}
+
+	// 投票人概况
+	// ticket_balance: 手中剩余的票数
+	// total_spent: 已投出的票数
+	// delegated_to: 把投票权委托给了谁
+	// votes: 投给每个候选人的票数
+	#[derive(scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, Debug, PartialEq, Eq))]
+	pub struct VoterProfile {
+		ticket_balance: u128,
+		total_spent: u128,
+		delegated_to: Option<AccountId>,
+		votes: Vec<(AccountId, u128)>,
+	}
+
+	// 合约状态快照, 用于链下存档
+	// candidates: 按加入顺序排列的候选人
+	// votes: 与candidates一一对应的票数
+	// block_number: 导出时的区块号
+	#[derive(scale::Encode, scale::Decode)]
+	#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, Debug, PartialEq, Eq))]
+	pub struct StateSnapshot {
+		candidates: Vec<AccountId>,
+		votes: Vec<u128>,
+		total_tokens: u128,
+		balance_tokens: u128,
+		block_number: BlockNumber,
+	}
+
+	// 投票回执, 每次投票成功后生成一张
+	// voter: 投票人
+	// candidate: 候选人
+	// amount: 投出的票数
+	// block: 投票时的区块号
+	#[derive(Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+	#[cfg_attr(
+		feature = "std",
+		derive(
+			scale_info::TypeInfo,
+			ink_storage::traits::StorageLayout,
+			Debug,
+			PartialEq,
+			Eq,
+		)
+	)]
+	pub struct ReceiptDetails {
+		voter: AccountId,
+		candidate: AccountId,
+		amount: u128,
+		block: BlockNumber,
+	}
+
+	// 等待批准的多签操作
+	// selector: 要执行的操作, 见ACTION_*
+	// args: 操作参数的SCALE编码
+	// approvals: 已批准的管理员
+	#[derive(Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+	#[cfg_attr(
+		feature = "std",
+		derive(
+			scale_info::TypeInfo,
+			ink_storage::traits::StorageLayout,
+			Debug,
+			PartialEq,
+			Eq,
+		)
+	)]
+	pub struct PendingAction {
+		selector: [u8; 4],
+		args: Vec<u8>,
+		approvals: Vec<AccountId>,
+	}
+
+	// 一次投票检查通过后各项数值的新值, 由prepare_vote计算, apply_vote写入
+	struct PendingVote {
+		amount: u128,
+		fee: u128,
+		ticket_num: u128,
+		vote_num: u128,
+		votes_received: u128,
+		raw_votes: u128,
 	}
 	// 定义持久化变量
 	// votes_received: 每个用户获取的投票数量
@@ -25,321 +453,10494 @@ mod voting {
 	// in_candidate_list: 冗余信息用于快速判断某个用户是否在可投票列表中
 	// total balance: 总的票数上限
 	// balance_token: 剩余票数
+	// max_supply: 增发后总票数的上限
 	// token_price: 每张票的价格
+	// min_price: 票价下限
 	// vote_num: 谁投了谁几票
 	// voter_balance: 投票人买了几张票
+	// admins: 管理员列表, 可在部署后增删候选人
+	// required_approvals: 管理员操作需要的批准人数, 大于1时只能通过多签操作执行
+	// pending_actions: 等待批准的多签操作
+	// next_action_id: 下一个多签操作的id, 只增不减
+	// executing_action: 是否正在执行已批准的多签操作
+	// pending_admin: 等待转移的管理员身份, (原管理员, 新管理员)
+	// deadline: 投票截止时间戳(毫秒), 之后不能再买票和投票
+	// mode: 计票方式
+	// refund_dust: 买票时不足一张票的零头, 可由购票人领回
+	// whitelist_enabled: 是否只允许白名单中的用户买票
+	// merkle_root: 白名单默克尔树的根, 叶子为sha256(投票人账户)
+	// paused: 合约是否被管理员暂停
+	// delegations: 投票人把投票权委托给了谁
+	// phase: 当前投票阶段
+	// refund_credits: 退票后可领回的金额
+	// ticket_payments: 开启require_payment时每个购票人(买到的票数, 实际支付的金额), 退款按平均票价计算
+	// voter_candidates: 投票人投过的候选人, 按第一次投票的顺序
+	// ranked_ballots: 排序投票中投票人对候选人的偏好顺序
+	// voter_weights: 投票人每投一票计入候选人的票数, 默认为1
+	// snapshots: 快照id对应的各候选人票数
+	// snapshot_blocks: 快照id对应的区块高度
+	// max_snapshots: 快照数量上限
+	// max_votes_per_candidate: 每个候选人最多能获得的票数
+	// seats: 选出的获胜者人数
+	// max_votes_per_voter_per_candidate: 每个投票人最多能投给同一个候选人的票数
+	// min_votes_threshold: 候选人进入结果所需的最低票数
+	// max_tickets_per_voter: 每个投票人最多能买的票数
+	// vote_start_time: 部署时的时间戳(毫秒)
+	// time_weighted: 是否按时间加权计票
+	// raw_votes_cast: 每个候选人获得的未加权票数
+	// stopped: 合约是否被紧急停止, 停止后不能再修改状态
+	// emergency_refunds: 紧急停止时投票人手中未投出的票, 可由投票人领回
+	// total_abstentions: 弃权票总数
+	// abstentions: 每个投票人投出的弃权票数
+	// proxy_contract: 可以代替用户投票的代理合约
+	// candidate_list_locked: 候选人列表是否已锁定, 锁定后不能解锁
+	// metadata: 选举的元数据
+	// metadata_updated_at: 元数据最后一次更新时的区块号
+	// token_contract: 按代币持有量计票时使用的PSP22代币合约
+	// vote_expiry_blocks: 投票在多少个区块后过期, 为0时永不过期
+	// vote_timestamps: 投票人最后一次给某候选人投票时的区块号
+	// commitments: 投票人提交的投票承诺, 揭示后删除
+	// candidate_aliases: 候选人的显示名称, 允许重名
+	// registered_voters: 管理员登记的有投票资格的用户
+	// has_purchased: 买过票的用户
+	// vetoes: 每个候选人被否决的次数
+	// veto_voters: 投票人否决过的候选人, 用于防止重复否决
+	// veto_threshold_bps: 否决生效的比例, 单位为万分之一
+	// quorum_votes: 选举有效所需的最低总票数
+	// require_payment: 买票时是否需要转入等额的原生代币
+	// proxy_allowances: (投票人, 代理人)对应的代理投票额度
+	// vote_timestamps_by_block: 每个区块中的投票记录(投票人, 候选人, 票数)
+	// vote_block_log: (候选人, 区块号)对应的该区块内得到的票数(未加权), 只保留最近max_history_blocks个区块
+	// max_history_blocks: 投票记录保留最近多少个区块
+	// auction_step: 每卖出多少张票票价上涨1
+	// election_id: 选举编号
+	// conviction_period: 信念投票中票数倍数加1所需的区块数
+	// max_conviction_multiplier: 信念投票的最大倍数
+	// conviction_bonus: 信念投票已额外计入候选人的票数, 键为(投票人, 候选人)
+	// warning_window_ms: 截止前多少毫秒开始提醒未投出的票
+	// decay_rate_bps_per_block: 每个区块投票有效权重衰减的比例
+	// stake_token: 质押换票使用的PSP22代币合约
+	// staked_amount: 每个投票人质押的代币数量
+	// staked_tickets: 每个投票人质押换到且还没投出的票数, 手中的票只有超出这部分的才能转让或退回
+	// staked_tickets_cast: 每个投票人已经投出的质押票数, 撤回时重新锁定
+	// fee_bps: 每次投票扣除的手续费比例
+	// fee_treasury: 收取手续费的账户
+	// treasury_votes: 每个账户收取的手续费票数
+	// insurance: 投票人为投给某候选人的票投保的票数, 键为(投票人, 候选人)
+	// receipts: 每个投票人得到的投票回执id
+	// receipt_details: 回执id对应的投票信息
+	// next_receipt_id: 下一张回执的id, 只增不减, 重置选举后也不会重复
+	// flash_hook_contract: 闪电投票时调用的回调合约
+	// max_votes_share_bps: 每个候选人得票占比的上限
+	// min_votes_for_share_cap: 开始检查得票占比上限所需的总票数
+	// total_votes_cast: 所有候选人得到的总票数, 随投票, 撤回和重置更新
+	// token_weight_divisor: 每锁定多少个代币得到1点代币权重
+	// max_token_weight: 代币权重的上限
+	// locked_tokens: 每个投票人为代币加权投票锁定的代币数量, 投票结束后才能取回
+	// spent_tickets: 已花费的票数, 包括投出后没有撤回的票, 作废的质押票和紧急停止后领回的票
 	#[ink(storage)]
 	pub struct Voting {
-		votes_received: StorageHashMap<AccountId, u32>,
+		votes_received: StorageHashMap<AccountId, u128>,
 		candidate_list: StorageVec<AccountId>,
 		in_candidate_list: StorageHashMap<AccountId, ()>,
-		total_tokens: u32,
-		balance_tokens: u32,
-		token_price: u32,
-		vote_num: StorageHashMap<(AccountId, AccountId), u32>,
-		voter_balance: StorageHashMap<AccountId, u32>,
+		total_tokens: u128,
+		balance_tokens: u128,
+		max_supply: u128,
+		token_price: u128,
+		min_price: u128,
+		vote_num: StorageHashMap<(AccountId, AccountId), u128>,
+		voter_balance: StorageHashMap<AccountId, u128>,
+		admins: StorageVec<AccountId>,
+		required_approvals: u32,
+		pending_actions: StorageHashMap<u32, PendingAction>,
+		next_action_id: u32,
+		executing_action: bool,
+		pending_admin: Option<(AccountId, AccountId)>,
+		deadline: u64,
+		mode: VotingMode,
+		refund_dust: StorageHashMap<AccountId, u128>,
+		whitelist_enabled: bool,
+		merkle_root: [u8; 32],
+		paused: bool,
+		delegations: StorageHashMap<AccountId, AccountId>,
+		phase: VotingPhase,
+		refund_credits: StorageHashMap<AccountId, u128>,
+		ticket_payments: StorageHashMap<AccountId, (u128, Balance)>,
+		voter_candidates: StorageHashMap<AccountId, Vec<AccountId>>,
+		ranked_ballots: StorageHashMap<AccountId, Vec<AccountId>>,
+		voter_weights: StorageHashMap<AccountId, u128>,
+		snapshots: StorageHashMap<u32, Vec<VoteOfCandidate>>,
+		snapshot_blocks: StorageHashMap<u32, BlockNumber>,
+		max_snapshots: u32,
+		max_votes_per_candidate: u128,
+		seats: u32,
+		max_votes_per_voter_per_candidate: u128,
+		min_votes_threshold: u128,
+		max_tickets_per_voter: u128,
+		vote_start_time: u64,
+		time_weighted: bool,
+		raw_votes_cast: StorageHashMap<AccountId, u128>,
+		stopped: bool,
+		emergency_refunds: StorageHashMap<AccountId, u128>,
+		total_abstentions: u128,
+		abstentions: StorageHashMap<AccountId, u128>,
+		proxy_contract: Option<AccountId>,
+		candidate_list_locked: bool,
+		metadata: String,
+		metadata_updated_at: BlockNumber,
+		token_contract: Option<AccountId>,
+		vote_expiry_blocks: u32,
+		vote_timestamps: StorageHashMap<(AccountId, AccountId), BlockNumber>,
+		commitments: StorageHashMap<AccountId, [u8; 32]>,
+		candidate_aliases: StorageHashMap<AccountId, String>,
+		registered_voters: StorageHashMap<AccountId, ()>,
+		has_purchased: StorageHashMap<AccountId, ()>,
+		vetoes: StorageHashMap<AccountId, u32>,
+		veto_voters: StorageHashMap<(AccountId, AccountId), ()>,
+		veto_threshold_bps: u32,
+		quorum_votes: u128,
+		require_payment: bool,
+		proxy_allowances: StorageHashMap<(AccountId, AccountId), u128>,
+		vote_timestamps_by_block: StorageHashMap<BlockNumber, Vec<(AccountId, AccountId, u128)>>,
+		vote_block_log: StorageHashMap<(AccountId, BlockNumber), u128>,
+		max_history_blocks: u32,
+		auction_step: u128,
+		election_id: u32,
+		conviction_period: u32,
+		max_conviction_multiplier: u128,
+		conviction_bonus: StorageHashMap<(AccountId, AccountId), u128>,
+		warning_window_ms: u64,
+		decay_rate_bps_per_block: u32,
+		stake_token: Option<AccountId>,
+		staked_amount: StorageHashMap<AccountId, Balance>,
+		staked_tickets: StorageHashMap<AccountId, u128>,
+		staked_tickets_cast: StorageHashMap<AccountId, u128>,
+		fee_bps: u32,
+		fee_treasury: AccountId,
+		treasury_votes: StorageHashMap<AccountId, u128>,
+		insurance: StorageHashMap<(AccountId, AccountId), u128>,
+		receipts: StorageHashMap<AccountId, Vec<u32>>,
+		receipt_details: StorageHashMap<u32, ReceiptDetails>,
+		next_receipt_id: u32,
+		flash_hook_contract: Option<AccountId>,
+		max_votes_share_bps: u32,
+		min_votes_for_share_cap: u128,
+		total_votes_cast: u128,
+		token_weight_divisor: u128,
+		max_token_weight: u128,
+		locked_tokens: StorageHashMap<AccountId, Balance>,
+		spent_tickets: u128,
+	}
+
+	// 投票触发事件定义, 带上投票后的状态方便链下索引
+	// voter_remaining: 投票人剩余的票数
+	// candidate_total: 候选人投票后的总票数
+	#[ink(event)]
+	pub struct VoteCast {
+		#[ink(topic)]
+		from: AccountId,
+		#[ink(topic)]
+		to: AccountId,
+		amount: u128,
+		voter_remaining: u128,
+		candidate_total: u128,
+		block_number: BlockNumber,
+		election_id: u32,
+	}
+
+	// 添加候选人触发事件定义
+	#[ink(event)]
+	pub struct CandidateAdded {
+		#[ink(topic)]
+		candidate: AccountId,
+		election_id: u32,
+	}
+
+	// 批量添加候选人触发事件定义
+	#[ink(event)]
+	pub struct CandidatesBatchAdded {
+		count: u32,
+		election_id: u32,
+	}
+
+	// 移除候选人触发事件定义
+	#[ink(event)]
+	pub struct CandidateRemoved {
+		#[ink(topic)]
+		candidate: AccountId,
+		election_id: u32,
+	}
+
+	// 候选人退出选举触发事件定义
+	#[ink(event)]
+	pub struct CandidateWithdrew {
+		#[ink(topic)]
+		candidate: AccountId,
+		votes_returned: u128,
+		election_id: u32,
+	}
+
+	// 转让选票触发事件定义
+	#[ink(event)]
+	pub struct TicketTransferred {
+		#[ink(topic)]
+		from: AccountId,
+		#[ink(topic)]
+		to: AccountId,
+		amount: u128,
+		election_id: u32,
+	}
+
+	// 暂停合约触发事件定义
+	#[ink(event)]
+	pub struct ContractPaused {
+		#[ink(topic)]
+		admin: AccountId,
+		election_id: u32,
+	}
+
+	// 恢复合约触发事件定义
+	#[ink(event)]
+	pub struct ContractUnpaused {
+		#[ink(topic)]
+		admin: AccountId,
+		election_id: u32,
+	}
+
+	// 批量投票触发事件定义, 整个批次只触发一次
+	#[ink(event)]
+	pub struct BatchVoteEvent {
+		#[ink(topic)]
+		owner: AccountId,
+		total_cast: u128,
+		election_id: u32,
+	}
+
+	// 批量买票触发事件定义
+	#[ink(event)]
+	pub struct BatchPurchase {
+		total_bought: u128,
+		election_id: u32,
+	}
+
+	// 撤回投票触发事件定义
+	#[ink(event)]
+	pub struct VoteRevoked {
+		#[ink(topic)]
+		owner: AccountId,
+		#[ink(topic)]
+		candidate: AccountId,
+		amount: u128,
+		election_id: u32,
+	}
+
+	// 委托投票触发事件定义
+	#[ink(event)]
+	pub struct VoteDelegated {
+		#[ink(topic)]
+		from: AccountId,
+		#[ink(topic)]
+		delegate: AccountId,
+		election_id: u32,
+	}
+
+	// 增发选票触发事件定义
+	#[ink(event)]
+	pub struct TokensMinted {
+		amount: u128,
+		new_total: u128,
+		election_id: u32,
+	}
+
+	// 票价变化触发事件定义
+	#[ink(event)]
+	pub struct TokenPriceChanged {
+		old_price: u128,
+		new_price: u128,
+		election_id: u32,
+	}
+
+	// 锁定候选人列表触发事件定义
+	#[ink(event)]
+	pub struct CandidateListLocked {
+		timestamp: u64,
+		election_id: u32,
+	}
+
+	// 发起管理员转移触发事件定义
+	#[ink(event)]
+	pub struct AdminTransferInitiated {
+		#[ink(topic)]
+		admin: AccountId,
+		#[ink(topic)]
+		pending_admin: AccountId,
+		election_id: u32,
+	}
+
+	// 完成管理员转移触发事件定义
+	#[ink(event)]
+	pub struct AdminTransferCompleted {
+		#[ink(topic)]
+		old_admin: AccountId,
+		#[ink(topic)]
+		new_admin: AccountId,
+		election_id: u32,
+	}
+
+	// 紧急停止触发事件定义
+	#[ink(event)]
+	pub struct EmergencyStop {
+		#[ink(topic)]
+		admin: AccountId,
+		timestamp: u64,
+		election_id: u32,
+	}
+
+	// 重置选举触发事件定义
+	#[ink(event)]
+	pub struct ElectionReset {
+		#[ink(topic)]
+		admin: AccountId,
+		election_id: u32,
+	}
+
+	// 导入状态快照触发事件定义
+	// block_number: 快照导出时的区块号
+	#[ink(event)]
+	pub struct StateImported {
+		block_number: BlockNumber,
+		election_id: u32,
+	}
+
+	// 管理员修正候选人票数触发事件定义
+	#[ink(event)]
+	pub struct VoteCountOverridden {
+		#[ink(topic)]
+		candidate: AccountId,
+		old_count: u128,
+		new_count: u128,
+		election_id: u32,
 	}
 
-	// 投票触发事件定义
+	// 候选人转让得票触发事件定义
 	#[ink(event)]
-	pub struct VoteEvent {
+	pub struct VotesTransferred {
 		#[ink(topic)]
 		from: AccountId,
 		#[ink(topic)]
 		to: AccountId,
+		amount: u128,
+		election_id: u32,
+	}
+
+	// 提醒投票人手中的票即将过期触发事件定义
+	// ms_remaining: 距离截止时间的毫秒数
+	#[ink(event)]
+	pub struct TicketsAboutToExpire {
+		#[ink(topic)]
+		voter: AccountId,
+		balance: u128,
+		ms_remaining: u64,
+		election_id: u32,
+	}
+
+	// 投票过期触发事件定义
+	#[ink(event)]
+	pub struct VoteExpired {
+		#[ink(topic)]
+		owner: AccountId,
+		#[ink(topic)]
+		candidate: AccountId,
+		amount: u128,
+		election_id: u32,
+	}
+
+	// 发起多签操作触发事件定义
+	#[ink(event)]
+	pub struct ActionProposed {
+		#[ink(topic)]
+		id: u32,
+		#[ink(topic)]
+		proposer: AccountId,
+		selector: [u8; 4],
+		election_id: u32,
+	}
+
+	// 批准多签操作触发事件定义
+	#[ink(event)]
+	pub struct ActionApproved {
+		#[ink(topic)]
+		id: u32,
+		#[ink(topic)]
+		admin: AccountId,
+		election_id: u32,
+	}
+
+	// 执行多签操作触发事件定义
+	#[ink(event)]
+	pub struct ActionExecuted {
+		#[ink(topic)]
+		id: u32,
+		election_id: u32,
+	}
+
+	// 投票阶段变化触发事件定义
+	#[ink(event)]
+	pub struct PhaseAdvanced {
+		phase: VotingPhase,
+		election_id: u32,
 	}
 
 	impl Voting {
 		#[ink(constructor)]
-		pub fn new(lists: Vec<AccountId>, total_tokens: u32, token_price: u32) -> Self {
+		pub fn new(lists: Vec<AccountId>, config: VotingConfig) -> Self {
+			Self::try_new(lists, config).expect("invalid voting config")
+		}
+
+		// 检查参数后创建合约, 参数不合法时返回错误
+		// ink!的构造函数只能返回Self, 所以new遇到错误时仍然会panic
+		pub fn try_new(lists: Vec<AccountId>, config: VotingConfig) -> Result<Self> {
 			let in_candidate_list: StorageHashMap<_, _, _> =
 				lists.iter().copied().map(|x| (x, ())).collect();
 			let candidate_list: StorageVec<_> = lists.iter().copied().collect();
-			assert!(in_candidate_list.len() == candidate_list.len());
-			Self {
+			if in_candidate_list.len() != candidate_list.len() {
+				return Err(VotingError::DuplicateCandidate);
+			}
+			if config.token_price < config.min_price {
+				return Err(VotingError::PriceBelowMinimum);
+			}
+			if config.total_tokens > config.max_supply {
+				return Err(VotingError::MaxSupplyExceeded);
+			}
+			if config.metadata.len() > MAX_METADATA_LEN {
+				return Err(VotingError::MetadataTooLong);
+			}
+			if config.fee_bps > 10_000 {
+				return Err(VotingError::InvalidFee);
+			}
+			if config.max_votes_share_bps > 10_000 {
+				return Err(VotingError::InvalidVoteShare);
+			}
+			if config.token_contract.is_some()
+				&& (config.token_weight_divisor == 0 || config.max_token_weight == 0)
+			{
+				return Err(VotingError::InvalidTokenWeight);
+			}
+			let mut admins = StorageVec::new();
+			admins.push(Self::env().caller());
+			Ok(Self {
 				candidate_list,
 				votes_received: StorageHashMap::default(),
 				in_candidate_list,
-				total_tokens,
-				balance_tokens: total_tokens,
-				token_price,
+				total_tokens: config.total_tokens,
+				balance_tokens: config.total_tokens,
+				max_supply: config.max_supply,
+				token_price: config.token_price,
+				min_price: config.min_price,
 				vote_num: StorageHashMap::default(),
 				voter_balance: StorageHashMap::default(),
+				admins,
+				required_approvals: 1,
+				pending_actions: StorageHashMap::default(),
+				next_action_id: 0,
+				executing_action: false,
+				pending_admin: None,
+				deadline: Self::env().block_timestamp() + config.duration_ms,
+				mode: config.mode,
+				refund_dust: StorageHashMap::default(),
+				whitelist_enabled: config.enable_whitelist,
+				merkle_root: config.merkle_root,
+				paused: false,
+				delegations: StorageHashMap::default(),
+				phase: VotingPhase::Registration,
+				refund_credits: StorageHashMap::default(),
+				ticket_payments: StorageHashMap::default(),
+				voter_candidates: StorageHashMap::default(),
+				ranked_ballots: StorageHashMap::default(),
+				voter_weights: StorageHashMap::default(),
+				snapshots: StorageHashMap::default(),
+				snapshot_blocks: StorageHashMap::default(),
+				max_snapshots: config.max_snapshots,
+				max_votes_per_candidate: config.max_votes_per_candidate,
+				seats: config.seats,
+				max_votes_per_voter_per_candidate: config.max_votes_per_voter_per_candidate,
+				min_votes_threshold: config.min_votes_threshold,
+				max_tickets_per_voter: config.max_tickets_per_voter,
+				vote_start_time: Self::env().block_timestamp(),
+				time_weighted: config.time_weighted,
+				raw_votes_cast: StorageHashMap::default(),
+				stopped: false,
+				emergency_refunds: StorageHashMap::default(),
+				total_abstentions: 0,
+				abstentions: StorageHashMap::default(),
+				proxy_contract: None,
+				candidate_list_locked: false,
+				metadata: config.metadata,
+				metadata_updated_at: Self::env().block_number(),
+				token_contract: config.token_contract,
+				vote_expiry_blocks: config.vote_expiry_blocks,
+				vote_timestamps: StorageHashMap::default(),
+				commitments: StorageHashMap::default(),
+				candidate_aliases: StorageHashMap::default(),
+				registered_voters: StorageHashMap::default(),
+				has_purchased: StorageHashMap::default(),
+				vetoes: StorageHashMap::default(),
+				veto_voters: StorageHashMap::default(),
+				veto_threshold_bps: config.veto_threshold_bps,
+				quorum_votes: config.quorum_votes,
+				require_payment: config.require_payment,
+				proxy_allowances: StorageHashMap::default(),
+				vote_timestamps_by_block: StorageHashMap::default(),
+				vote_block_log: StorageHashMap::default(),
+				max_history_blocks: config.max_history_blocks,
+				auction_step: config.auction_step,
+				election_id: config.election_id,
+				conviction_period: config.conviction_period,
+				max_conviction_multiplier: config.max_conviction_multiplier,
+				conviction_bonus: StorageHashMap::default(),
+				warning_window_ms: config.warning_window_ms,
+				decay_rate_bps_per_block: config.decay_rate_bps_per_block,
+				stake_token: config.stake_token,
+				staked_amount: StorageHashMap::default(),
+				staked_tickets: StorageHashMap::default(),
+				staked_tickets_cast: StorageHashMap::default(),
+				fee_bps: config.fee_bps,
+				fee_treasury: config.fee_treasury,
+				treasury_votes: StorageHashMap::default(),
+				insurance: StorageHashMap::default(),
+				receipts: StorageHashMap::default(),
+				receipt_details: StorageHashMap::default(),
+				next_receipt_id: 0,
+				flash_hook_contract: config.flash_hook_contract,
+				max_votes_share_bps: config.max_votes_share_bps,
+				min_votes_for_share_cap: config.min_votes_for_share_cap,
+				total_votes_cast: 0,
+				token_weight_divisor: config.token_weight_divisor,
+				max_token_weight: config.max_token_weight,
+				locked_tokens: StorageHashMap::default(),
+				spent_tickets: 0,
+			})
+		}
+
+		// 用source当前的候选人和参数创建新的选举, 票数和购票记录都从零开始, 调用者为管理员
+		// ink!的构造函数不能以合约实例为参数, 所以这只是普通的关联函数
+		pub fn clone_from(source: &Voting) -> Self {
+			let candidates = source.candidate_list.iter().copied().collect();
+			Self::try_new(candidates, source.current_config()).expect("invalid voting config")
+		}
+
+		// 选举编号
+		#[ink(message)]
+		pub fn get_election_id(&self) -> u32 {
+			self.election_id
+		}
+
+		// 获取重新部署同样选举所需的主要参数: (候选人列表, 总票数, 票价)
+		#[ink(message)]
+		pub fn clone_params(&self) -> (Vec<AccountId>, u128, u128) {
+			(
+				self.candidate_list.iter().copied().collect(),
+				self.total_tokens,
+				self.token_price,
+			)
+		}
+
+		// 添加候选人, 仅管理员可调用
+		#[ink(message)]
+		pub fn add_candidate(&mut self, candidate: AccountId) -> Result<()> {
+			self.ensure_admin()?;
+			self.ensure_candidate_list_unlocked()?;
+			if !self.insert_candidate(candidate) {
+				return Err(VotingError::CandidateAlreadyExists);
 			}
+			self.env().emit_event(CandidateAdded {
+				candidate,
+				election_id: self.election_id,
+			});
+			Ok(())
 		}
 
+		// 批量添加候选人, 仅管理员可调用, 跳过已存在和重复的用户, 返回实际添加的数量
 		#[ink(message)]
-		pub fn buy_ticket(&mut self, owner: AccountId, value: u32) -> bool {
-			let amount = value / self.token_price;
-			// 确保剩余票数够
-			if amount > self.balance_tokens {
+		pub fn batch_add_candidates(&mut self, candidates: Vec<AccountId>) -> Result<u32> {
+			self.ensure_admin()?;
+			self.ensure_candidate_list_unlocked()?;
+			let mut count: u32 = 0;
+			for candidate in candidates.into_iter() {
+				if self.insert_candidate(candidate) {
+					count += 1;
+				}
+			}
+			self.env().emit_event(CandidatesBatchAdded {
+				count,
+				election_id: self.election_id,
+			});
+			Ok(count)
+		}
+
+		// 内部辅助函数把用户加入候选人列表, 已经是候选人时返回false
+		fn insert_candidate(&mut self, candidate: AccountId) -> bool {
+			if self.in_candidate_list.contains_key(&candidate) {
 				return false;
 			}
-			// 用户ticket增加
-			if !self.voter_balance.contains_key(&owner) {
-				// 新用户直接插入数据
-				self.voter_balance.insert(owner, amount);
-			} else {
-				// 老用户做mutate
-				self.voter_balance.entry(owner).and_modify(|v| *v += amount);
+			self.candidate_list.push(candidate);
+			self.in_candidate_list.insert(candidate, ());
+			true
+		}
+
+		// 设置候选人的别名, 只能由候选人本人或管理员调用
+		#[ink(message)]
+		pub fn set_candidate_alias(&mut self, candidate: AccountId, alias: String) -> Result<()> {
+			self.ensure_not_stopped()?;
+			let caller = self.env().caller();
+			if caller != candidate && !self.is_admin(caller) {
+				return Err(VotingError::Unauthorized);
+			}
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return Err(VotingError::NotACandidate);
+			}
+			if alias.len() > MAX_ALIAS_LEN {
+				return Err(VotingError::AliasTooLong);
 			}
+			self.candidate_aliases.insert(candidate, alias);
+			Ok(())
+		}
 
-			// balance_tokens减少
-			self.balance_tokens -= amount;
+		// 获取候选人的别名
+		#[ink(message)]
+		pub fn get_candidate_alias(&self, candidate: AccountId) -> Option<String> {
+			self.candidate_aliases.get(&candidate).cloned()
+		}
 
-			true
+		// 锁定候选人列表, 仅管理员可调用
+		// 锁定后不能再增删候选人, 也不能解锁
+		#[ink(message)]
+		pub fn lock_candidate_list(&mut self) -> Result<()> {
+			self.ensure_admin()?;
+			self.ensure_candidate_list_unlocked()?;
+			self.candidate_list_locked = true;
+			self.env().emit_event(CandidateListLocked {
+				timestamp: self.env().block_timestamp(),
+				election_id: self.election_id,
+			});
+			Ok(())
 		}
-		// 剩余票数
+
+		// 候选人列表是否已锁定
 		#[ink(message)]
-		pub fn all_ticket_num(&mut self) -> u32 {
-			self.total_tokens
+		pub fn is_candidate_list_locked(&self) -> bool {
+			self.candidate_list_locked
 		}
-		// 剩余票数
+
+		// 移除候选人, 仅管理员可调用
+		// 已投给该候选人的票视为已花费, 不退还给投票人
 		#[ink(message)]
-		pub fn left_ticket_num(&mut self) -> u32 {
-			self.balance_tokens
+		pub fn remove_candidate(&mut self, candidate: AccountId) -> Result<()> {
+			self.ensure_admin()?;
+			self.ensure_candidate_list_unlocked()?;
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return Err(VotingError::NotACandidate);
+			}
+			self.take_candidate(candidate);
+			self.env().emit_event(CandidateRemoved {
+				candidate,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 调用者退出选举, 只能由候选人本人调用
+		// 投给该候选人的票作废, 投保的票退回投票人手中, 其余按未加权的票数退回剩余票数中
+		#[ink(message)]
+		pub fn withdraw_candidacy(&mut self) -> Result<()> {
+			self.ensure_not_stopped()?;
+			let candidate = self.env().caller();
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return Err(VotingError::NotACandidate);
+			}
+			// 退还的票数不超过投票人现在投给该候选人的票数
+			let mut refunds = Vec::new();
+			let mut refunded: u128 = 0;
+			for ((voter, _), insured) in self.insurance.iter().filter(|((_, x), _)| *x == candidate)
+			{
+				let amount = (*insured).min(self.callee_vote_of(*voter, candidate));
+				let ticket_num = self
+					.voter_balance
+					.get(voter)
+					.unwrap_or(&0)
+					.checked_add(amount)
+					.ok_or(VotingError::Overflow)?;
+				refunds.push((*voter, ticket_num));
+				refunded = refunded.saturating_add(amount);
+			}
+			let raw_votes = self.raw_votes_for(candidate);
+			let votes_returned = raw_votes.saturating_sub(refunded);
+			let balance_tokens = self
+				.balance_tokens
+				.checked_add(votes_returned)
+				.ok_or(VotingError::Overflow)?;
+			self.take_candidate(candidate);
+			self.spent_tickets = self.spent_tickets.saturating_sub(raw_votes);
+			for (voter, ticket_num) in refunds.into_iter() {
+				let returned = ticket_num.saturating_sub(self.voter_ticket_balance(voter));
+				self.voter_balance.insert(voter, ticket_num);
+				self.relock_staked_tickets(voter, returned);
+			}
+			self.balance_tokens = balance_tokens;
+			self.env().emit_event(CandidateWithdrew {
+				candidate,
+				votes_returned,
+				election_id: self.election_id,
+			});
+			Ok(())
 		}
-		// 购买一票需要的价格
+
+		// 推进到下一个投票阶段, 仅管理员可调用
 		#[ink(message)]
-		pub fn price_of_ticket(&mut self) -> u32 {
-			self.token_price
+		pub fn advance_phase(&mut self) -> Result<()> {
+			self.ensure_admin()?;
+			self.phase = match self.phase {
+				VotingPhase::Registration => VotingPhase::Active,
+				VotingPhase::Active => VotingPhase::Ended,
+				VotingPhase::Ended => return Err(VotingError::WrongPhase),
+			};
+			self.env().emit_event(PhaseAdvanced {
+				phase: self.phase,
+				election_id: self.election_id,
+			});
+			Ok(())
 		}
-		// 某用户手中的票数
+
+		// 发起管理员转移, 仅管理员可调用, 把调用者的管理员身份转给new_admin
+		// new_admin调用accept_admin之前原管理员仍保留权限, 再次调用会覆盖之前的new_admin
 		#[ink(message)]
-		pub fn voter_ticket_balance(&mut self, owner: AccountId) -> u32 {
-			*self.voter_balance.get(&owner).unwrap_or(&0)
+		pub fn transfer_admin(&mut self, new_admin: AccountId) -> Result<()> {
+			self.ensure_admin()?;
+			let caller = self.env().caller();
+			self.initiate_admin_transfer(caller, new_admin);
+			Ok(())
 		}
-		// 获取可被投票的用户数量
+
+		// 内部辅助函数记录待接受的管理员转移
+		// 通过多签操作转移时old_admin来自操作参数, 而不是执行操作的管理员
+		fn initiate_admin_transfer(&mut self, old_admin: AccountId, new_admin: AccountId) {
+			self.pending_admin = Some((old_admin, new_admin));
+			self.env().emit_event(AdminTransferInitiated {
+				admin: old_admin,
+				pending_admin: new_admin,
+				election_id: self.election_id,
+			});
+		}
+
+		// 接受管理员身份, 只能由transfer_admin指定的账户调用
 		#[ink(message)]
-		pub fn get_candidates_len(&mut self) -> u32 {
-			self.candidate_list.len()
+		pub fn accept_admin(&mut self) -> Result<()> {
+			self.ensure_not_stopped()?;
+			let caller = self.env().caller();
+			let old_admin = match self.pending_admin {
+				Some((old_admin, new_admin)) if new_admin == caller => old_admin,
+				_ => return Err(VotingError::Unauthorized),
+			};
+			let admins: Vec<_> = self
+				.admins
+				.iter()
+				.map(|x| if *x == old_admin { caller } else { *x })
+				.collect();
+			self.replace_admins(admins);
+			self.pending_admin = None;
+			self.env().emit_event(AdminTransferCompleted {
+				old_admin,
+				new_admin: caller,
+				election_id: self.election_id,
+			});
+			Ok(())
 		}
-		// 获取可被投票的用户
+
+		// 获取管理员列表
 		#[ink(message)]
-		pub fn get_candidates(&mut self) -> Vec<AccountId> {
-			self.candidate_list.iter().copied().collect()
+		pub fn get_admins(&self) -> Vec<AccountId> {
+			self.admins.iter().copied().collect()
 		}
-		// 获取当前各用户投票票数状态
+
+		// 某账户是否是管理员
 		#[ink(message)]
-		pub fn get_current_votes(&mut self) -> Vec<VoteOfCandidate> {
-			let candidate_list: Vec<_> = self.candidate_list.iter().copied().collect();
-			let mut current_vote: Vec<VoteOfCandidate> = Vec::new();
-			for x in candidate_list.clone().into_iter() {
-				let candidate = VoteOfCandidate {
-					candidate: x,
-					vote: self.my_value_or_zero(x),
-				};
-				current_vote.push(candidate);
-			}
-			current_vote
+		pub fn is_admin(&self, account: AccountId) -> bool {
+			self.admins.iter().any(|x| *x == account)
+		}
+
+		// 管理员操作需要的批准人数
+		#[ink(message)]
+		pub fn required_approvals(&self) -> u32 {
+			self.required_approvals
+		}
+
+		// 修改管理员列表和需要的批准人数, 仅管理员可调用
+		// 未完成的管理员转移会被取消
+		#[ink(message)]
+		pub fn set_admins(
+			&mut self,
+			admins: Vec<AccountId>,
+			required_approvals: u32,
+		) -> Result<()> {
+			self.ensure_admin()?;
+			let unique: StorageHashMap<_, _, _> = admins.iter().copied().map(|x| (x, ())).collect();
+			if admins.is_empty()
+				|| unique.len() as usize != admins.len()
+				|| required_approvals == 0
+				|| required_approvals as usize > admins.len()
+			{
+				return Err(VotingError::InvalidApprovalThreshold);
+			}
+			self.replace_admins(admins);
+			self.required_approvals = required_approvals;
+			self.pending_admin = None;
+			Ok(())
+		}
+
+		// 发起多签操作, 仅管理员可调用, 发起人自动批准, 返回操作的id
+		#[ink(message)]
+		pub fn propose_action(&mut self, selector: [u8; 4], args: Vec<u8>) -> Result<u32> {
+			self.ensure_not_stopped()?;
+			let caller = self.env().caller();
+			if !self.is_admin(caller) {
+				return Err(VotingError::Unauthorized);
+			}
+			if !Self::is_known_action(selector) {
+				return Err(VotingError::UnknownAction);
+			}
+			let id = self.next_action_id;
+			self.next_action_id = id.checked_add(1).ok_or(VotingError::Overflow)?;
+			self.pending_actions.insert(
+				id,
+				PendingAction {
+					selector,
+					args,
+					approvals: ink_prelude::vec![caller],
+				},
+			);
+			self.env().emit_event(ActionProposed {
+				id,
+				proposer: caller,
+				selector,
+				election_id: self.election_id,
+			});
+			Ok(id)
+		}
+
+		// 批准多签操作, 仅管理员可调用
+		#[ink(message)]
+		pub fn approve_action(&mut self, id: u32) -> Result<()> {
+			self.ensure_not_stopped()?;
+			let caller = self.env().caller();
+			if !self.is_admin(caller) {
+				return Err(VotingError::Unauthorized);
+			}
+			let action = self
+				.pending_actions
+				.get_mut(&id)
+				.ok_or(VotingError::ActionNotFound)?;
+			if action.approvals.contains(&caller) {
+				return Err(VotingError::AlreadyApproved);
+			}
+			action.approvals.push(caller);
+			self.env().emit_event(ActionApproved {
+				id,
+				admin: caller,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 执行批准人数已达到required_approvals的多签操作, 仅管理员可调用
+		// 只统计仍是管理员的批准人, 执行失败时操作保留
+		#[ink(message)]
+		pub fn execute_action(&mut self, id: u32) -> Result<()> {
+			self.ensure_not_stopped()?;
+			if !self.is_admin(self.env().caller()) {
+				return Err(VotingError::Unauthorized);
+			}
+			let action = self
+				.pending_actions
+				.get(&id)
+				.cloned()
+				.ok_or(VotingError::ActionNotFound)?;
+			let approvals = action
+				.approvals
+				.iter()
+				.filter(|x| self.is_admin(**x))
+				.count();
+			if (approvals as u32) < self.required_approvals {
+				return Err(VotingError::NotEnoughApprovals);
+			}
+			self.executing_action = true;
+			let ret = self.dispatch_action(action.selector, &action.args);
+			self.executing_action = false;
+			ret?;
+			self.pending_actions.take(&id);
+			self.env().emit_event(ActionExecuted {
+				id,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 获取等待批准的多签操作
+		#[ink(message)]
+		pub fn get_pending_action(&self, id: u32) -> Option<PendingAction> {
+			self.pending_actions.get(&id).cloned()
+		}
+
+		// 直接修改某候选人的票数, 仅管理员可调用, 用于修正错误数据
+		// 未加权票数按新旧票数的比例同步修改, 修改后的得票占比同样不能超过上限
+		#[ink(message)]
+		pub fn override_vote_count(&mut self, candidate: AccountId, new_count: u128) -> Result<()> {
+			self.ensure_admin()?;
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return Err(VotingError::NotACandidate);
+			}
+			self.ensure_vote_share(candidate, new_count)?;
+			let old_count = self.my_value_or_zero(candidate);
+			let raw_votes = match old_count {
+				0 => new_count,
+				_ => {
+					self.raw_votes_for(candidate)
+						.checked_mul(new_count)
+						.ok_or(VotingError::Overflow)?
+						/ old_count
+				}
+			};
+			self.total_votes_cast = self
+				.total_votes_cast
+				.saturating_sub(old_count)
+				.saturating_add(new_count);
+			self.votes_received.insert(candidate, new_count);
+			self.raw_votes_cast.insert(candidate, raw_votes);
+			self.env().emit_event(VoteCountOverridden {
+				candidate,
+				old_count,
+				new_count,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 重置选举, 仅管理员可调用
+		// 清空所有投票和购票记录, 剩余票数恢复为总票数, 回到报名阶段
+		// 候选人列表保持不变, 退票和零头的待领金额也保留
+		// 开启require_payment时投票人手中还没投出的票按买票时的平均票价退款, 质押换到的票不退款
+		#[ink(message)]
+		pub fn reset_election(&mut self) -> Result<()> {
+			self.ensure_admin()?;
+			if self.require_payment {
+				let voters: Vec<_> = self.voter_balance.keys().copied().collect();
+				for voter in voters {
+					let (refund, _) = self.paid_refund(voter, self.voter_ticket_balance(voter));
+					if refund > 0 {
+						let credit = self.refund_credits.entry(voter).or_insert(0);
+						*credit = credit.saturating_add(refund);
+					}
+				}
+			}
+			let payers: Vec<_> = self.ticket_payments.keys().copied().collect();
+			for payer in payers {
+				self.ticket_payments.take(&payer);
+			}
+			let candidates: Vec<_> = self.votes_received.keys().copied().collect();
+			for candidate in candidates {
+				self.votes_received.take(&candidate);
+			}
+			self.total_votes_cast = 0;
+			let candidates: Vec<_> = self.raw_votes_cast.keys().copied().collect();
+			for candidate in candidates {
+				self.raw_votes_cast.take(&candidate);
+			}
+			let pairs: Vec<_> = self.vote_num.keys().copied().collect();
+			for pair in pairs {
+				self.vote_num.take(&pair);
+			}
+			let pairs: Vec<_> = self.vote_timestamps.keys().copied().collect();
+			for pair in pairs {
+				self.vote_timestamps.take(&pair);
+			}
+			let voters: Vec<_> = self.voter_balance.keys().copied().collect();
+			for voter in voters {
+				self.voter_balance.take(&voter);
+			}
+			let voters: Vec<_> = self.staked_tickets.keys().copied().collect();
+			for voter in voters {
+				self.staked_tickets.take(&voter);
+			}
+			let voters: Vec<_> = self.staked_tickets_cast.keys().copied().collect();
+			for voter in voters {
+				self.staked_tickets_cast.take(&voter);
+			}
+			let voters: Vec<_> = self.voter_candidates.keys().copied().collect();
+			for voter in voters {
+				self.voter_candidates.take(&voter);
+			}
+			let voters: Vec<_> = self.delegations.keys().copied().collect();
+			for voter in voters {
+				self.delegations.take(&voter);
+			}
+			let voters: Vec<_> = self.ranked_ballots.keys().copied().collect();
+			for voter in voters {
+				self.ranked_ballots.take(&voter);
+			}
+			let voters: Vec<_> = self.abstentions.keys().copied().collect();
+			for voter in voters {
+				self.abstentions.take(&voter);
+			}
+			let voters: Vec<_> = self.commitments.keys().copied().collect();
+			for voter in voters {
+				self.commitments.take(&voter);
+			}
+			let voters: Vec<_> = self.has_purchased.keys().copied().collect();
+			for voter in voters {
+				self.has_purchased.take(&voter);
+			}
+			let candidates: Vec<_> = self.vetoes.keys().copied().collect();
+			for candidate in candidates {
+				self.vetoes.take(&candidate);
+			}
+			let pairs: Vec<_> = self.veto_voters.keys().copied().collect();
+			for pair in pairs {
+				self.veto_voters.take(&pair);
+			}
+			let pairs: Vec<_> = self.proxy_allowances.keys().copied().collect();
+			for pair in pairs {
+				self.proxy_allowances.take(&pair);
+			}
+			let blocks: Vec<_> = self.vote_timestamps_by_block.keys().copied().collect();
+			for block in blocks {
+				self.vote_timestamps_by_block.take(&block);
+			}
+			let pairs: Vec<_> = self.vote_block_log.keys().copied().collect();
+			for pair in pairs {
+				self.vote_block_log.take(&pair);
+			}
+			let pairs: Vec<_> = self.conviction_bonus.keys().copied().collect();
+			for pair in pairs {
+				self.conviction_bonus.take(&pair);
+			}
+			let treasuries: Vec<_> = self.treasury_votes.keys().copied().collect();
+			for treasury in treasuries {
+				self.treasury_votes.take(&treasury);
+			}
+			let pairs: Vec<_> = self.insurance.keys().copied().collect();
+			for pair in pairs {
+				self.insurance.take(&pair);
+			}
+			self.total_abstentions = 0;
+			self.spent_tickets = 0;
+			self.balance_tokens = self.total_tokens;
+			self.phase = VotingPhase::Registration;
+			self.env().emit_event(ElectionReset {
+				admin: self.env().caller(),
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 导入上一轮选举的票数, 仅管理员可在还没有人投票时调用
+		// 之后的投票会累加在导入的票数上
+		#[ink(message)]
+		pub fn import_previous_votes(&mut self, legacy: Vec<VoteOfCandidate>) -> Result<()> {
+			self.ensure_admin()?;
+			if !self.votes_received.is_empty() {
+				return Err(VotingError::VotesAlreadyCast);
+			}
+			let mut imported: Vec<(AccountId, u128)> = Vec::new();
+			for x in legacy.iter() {
+				if !self.in_candidate_list.contains_key(&x.candidate) {
+					return Err(VotingError::NotACandidate);
+				}
+				match imported.iter_mut().find(|(c, _)| *c == x.candidate) {
+					Some((_, votes)) => {
+						*votes = votes.checked_add(x.vote).ok_or(VotingError::Overflow)?
+					}
+					None => imported.push((x.candidate, x.vote)),
+				}
+			}
+			for (candidate, votes) in imported.into_iter().filter(|(_, v)| *v > 0) {
+				self.total_votes_cast = self.total_votes_cast.saturating_add(votes);
+				self.votes_received.insert(candidate, votes);
+			}
+			Ok(())
+		}
+
+		// 设置投票人的权重, 仅管理员可在报名阶段调用
+		// 投票时只扣除投出的票数, 候选人得到的票数乘以权重
+		#[ink(message)]
+		pub fn set_voter_weight(&mut self, voter: AccountId, weight: u128) -> Result<()> {
+			self.ensure_admin()?;
+			self.ensure_phase(VotingPhase::Registration)?;
+			if weight == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			self.voter_weights.insert(voter, weight);
+			Ok(())
+		}
+
+		// 获取投票人的权重
+		#[ink(message)]
+		pub fn get_voter_weight(&self, voter: AccountId) -> u128 {
+			*self.voter_weights.get(&voter).unwrap_or(&1)
+		}
+
+		// 当前投票阶段
+		#[ink(message)]
+		pub fn current_phase(&self) -> VotingPhase {
+			self.phase
+		}
+
+		// 紧急停止合约, 仅管理员可调用, 停止后不能恢复
+		// 投票人手中未投出的票转入emergency_refunds, 由投票人自行领回
+		#[ink(message)]
+		pub fn emergency_stop(&mut self) -> Result<()> {
+			self.ensure_admin()?;
+			let voters: Vec<_> = self.voter_balance.keys().copied().collect();
+			for voter in voters {
+				let held = self.voter_balance.take(&voter).unwrap_or(0);
+				// 质押换到的票不退款, 由unstake直接取回质押的代币
+				let tickets = held.saturating_sub(self.staked_tickets_of(voter));
+				if tickets > 0 {
+					self.emergency_refunds.insert(voter, tickets);
+				}
+				self.spent_tickets = self.spent_tickets.saturating_add(held - tickets);
+			}
+			self.stopped = true;
+			self.env().emit_event(EmergencyStop {
+				admin: self.env().caller(),
+				timestamp: self.env().block_timestamp(),
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 合约是否已被紧急停止
+		#[ink(message)]
+		pub fn is_stopped(&self) -> bool {
+			self.stopped
+		}
+
+		// 领回调用者在紧急停止时未投出的票, 返回领回的数量
+		// 开启require_payment时按买票时的平均票价转回原生代币, 转账失败时保留待领的票
+		#[ink(message)]
+		pub fn claim_emergency_refund(&mut self) -> u128 {
+			let caller = self.env().caller();
+			let tickets = self.emergency_refunds.take(&caller).unwrap_or(0);
+			if tickets == 0 {
+				return 0;
+			}
+			if self.require_payment {
+				let (refund, payment) = self.paid_refund(caller, tickets);
+				if refund > 0 && self.env().transfer(caller, refund).is_err() {
+					self.emergency_refunds.insert(caller, tickets);
+					return 0;
+				}
+				self.update_ticket_payment(caller, payment);
+			}
+			self.spent_tickets = self.spent_tickets.saturating_add(tickets);
+			tickets
+		}
+
+		// 登记代理合约, 仅管理员可调用, 传入None取消登记
+		#[ink(message)]
+		pub fn set_proxy_contract(&mut self, proxy: Option<AccountId>) -> Result<()> {
+			self.ensure_admin()?;
+			self.proxy_contract = proxy;
+			Ok(())
+		}
+
+		// 当前登记的代理合约
+		#[ink(message)]
+		pub fn proxy_contract(&self) -> Option<AccountId> {
+			self.proxy_contract
+		}
+
+		// 按代币持有量计票时使用的代币合约
+		#[ink(message)]
+		pub fn token_contract(&self) -> Option<AccountId> {
+			self.token_contract
+		}
+
+		// 锁定调用者的amount个代币, 代币权重按锁定的数量计算, 余额变化不影响已锁定的部分
+		#[ink(message)]
+		pub fn lock_tokens(&mut self, amount: Balance) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			let owner = self.env().caller();
+			let token = self
+				.token_contract
+				.ok_or(VotingError::TokenWeightDisabled)?;
+			if amount == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			let locked = self
+				.locked_tokens_of(owner)
+				.checked_add(amount)
+				.ok_or(VotingError::Overflow)?;
+			Self::token_transfer_from(token, owner, self.env().account_id(), amount)?;
+			self.locked_tokens.insert(owner, locked);
+			Ok(())
+		}
+
+		// 取回调用者锁定的全部代币, 返回取回的数量
+		// 只能在投票结束或紧急停止后调用, 防止把代币转给别人后再次投票
+		#[ink(message)]
+		pub fn unlock_tokens(&mut self) -> Result<Balance> {
+			let owner = self.env().caller();
+			let token = self
+				.token_contract
+				.ok_or(VotingError::TokenWeightDisabled)?;
+			if !self.stopped && !self.has_ended() && self.phase != VotingPhase::Ended {
+				return Err(VotingError::WrongPhase);
+			}
+			let locked = self.locked_tokens_of(owner);
+			if locked == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			Self::token_transfer(token, owner, locked)?;
+			self.locked_tokens.take(&owner);
+			Ok(locked)
+		}
+
+		// 某投票人锁定的代币数量
+		#[ink(message)]
+		pub fn locked_tokens_of(&self, owner: AccountId) -> Balance {
+			*self.locked_tokens.get(&owner).unwrap_or(&0)
+		}
+
+		// 修改选举元数据, 仅管理员可调用
+		#[ink(message)]
+		pub fn set_metadata(&mut self, new_metadata: String) -> Result<()> {
+			self.ensure_admin()?;
+			if new_metadata.len() > MAX_METADATA_LEN {
+				return Err(VotingError::MetadataTooLong);
+			}
+			self.metadata = new_metadata;
+			self.metadata_updated_at = self.env().block_number();
+			Ok(())
+		}
+
+		// 获取选举元数据
+		#[ink(message)]
+		pub fn get_metadata(&self) -> String {
+			self.metadata.clone()
+		}
+
+		// 获取元数据最后一次更新时的区块号
+		#[ink(message)]
+		pub fn metadata_updated_at(&self) -> BlockNumber {
+			self.metadata_updated_at
+		}
+
+		// 暂停合约, 暂停期间不能买票和投票, 仅管理员可调用
+		#[ink(message)]
+		pub fn pause(&mut self) -> Result<()> {
+			self.ensure_admin()?;
+			self.paused = true;
+			self.env().emit_event(ContractPaused {
+				admin: self.env().caller(),
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 恢复合约, 仅管理员可调用
+		#[ink(message)]
+		pub fn unpause(&mut self) -> Result<()> {
+			self.ensure_admin()?;
+			self.paused = false;
+			self.env().emit_event(ContractUnpaused {
+				admin: self.env().caller(),
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 合约是否被暂停
+		#[ink(message)]
+		pub fn is_paused(&self) -> bool {
+			self.paused
+		}
+
+		// 修改白名单默克尔树的根, 仅管理员可调用
+		#[ink(message)]
+		pub fn set_merkle_root(&mut self, root: [u8; 32]) -> Result<()> {
+			self.ensure_admin()?;
+			self.merkle_root = root;
+			Ok(())
+		}
+
+		// 白名单默克尔树的根
+		#[ink(message)]
+		pub fn get_merkle_root(&self) -> [u8; 32] {
+			self.merkle_root
+		}
+
+		// 开启或关闭白名单, 仅管理员可调用
+		#[ink(message)]
+		pub fn set_whitelist_enabled(&mut self, enabled: bool) -> Result<()> {
+			self.ensure_admin()?;
+			self.whitelist_enabled = enabled;
+			Ok(())
+		}
+
+		// 是否开启了白名单
+		#[ink(message)]
+		pub fn is_whitelist_enabled(&self) -> bool {
+			self.whitelist_enabled
+		}
+
+		// 用默克尔证明检查某用户是否在白名单中
+		#[ink(message)]
+		pub fn is_whitelisted(&self, voter: AccountId, proof: Vec<[u8; 32]>) -> bool {
+			self.verify_merkle_proof(voter, &proof)
+		}
+
+		#[ink(message, payable)]
+		pub fn buy_ticket(&mut self, owner: AccountId, value: Balance) -> Result<()> {
+			let mut paid = self.env().transferred_balance();
+			let ret = self.buy_ticket_paid(owner, value, None, &mut paid);
+			self.refund_payment(owner, paid);
+			ret
+		}
+		// 带默克尔证明买票, 开启白名单时需要用这种方式买票
+		#[ink(message, payable)]
+		pub fn buy_ticket_with_proof(
+			&mut self,
+			owner: AccountId,
+			value: Balance,
+			proof: Vec<[u8; 32]>,
+		) -> Result<()> {
+			let mut paid = self.env().transferred_balance();
+			let ret = self.buy_ticket_paid(owner, value, Some(&proof[..]), &mut paid);
+			self.refund_payment(owner, paid);
+			ret
+		}
+		// 开启require_payment时买票要从转入的金额paid中扣除整数张票的金额, 不够时不能买票
+		// 不足一张票的零头不收取, 和多转的部分一起退回
+		fn buy_ticket_paid(
+			&mut self,
+			owner: AccountId,
+			value: Balance,
+			proof: Option<&[[u8; 32]]>,
+			paid: &mut Balance,
+		) -> Result<()> {
+			if !self.require_payment {
+				return self.buy_ticket_with_optional_proof(owner, value, proof);
+			}
+			let price = self.current_ticket_price();
+			let cost = value - value % price;
+			if *paid < cost {
+				return Err(VotingError::InsufficientPayment);
+			}
+			let (bought, total_paid) = *self.ticket_payments.get(&owner).unwrap_or(&(0, 0));
+			let bought = bought
+				.checked_add(cost / price)
+				.ok_or(VotingError::Overflow)?;
+			let total_paid = total_paid.checked_add(cost).ok_or(VotingError::Overflow)?;
+			self.buy_ticket_with_optional_proof(owner, cost, proof)?;
+			*paid -= cost;
+			if bought > 0 {
+				self.ticket_payments.insert(owner, (bought, total_paid));
+			}
+			Ok(())
+		}
+		// 把没有用掉的转账退回给to, 未开启require_payment时转入的金额全部退回
+		// 退款失败时直接panic, 回滚整笔交易
+		fn refund_payment(&mut self, to: AccountId, excess: Balance) {
+			if excess == 0 {
+				return;
+			}
+			self.env()
+				.transfer(to, excess)
+				.expect("refund of excess payment failed");
+		}
+		fn buy_ticket_with_optional_proof(
+			&mut self,
+			owner: AccountId,
+			value: u128,
+			proof: Option<&[[u8; 32]]>,
+		) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Registration)?;
+			self.ensure_not_delegated(owner)?;
+			if self.whitelist_enabled
+				&& !proof.map_or(false, |x| self.verify_merkle_proof(owner, x))
+			{
+				return Err(VotingError::NotWhitelisted);
+			}
+			// 按买票前的价格计算, 一次购买中票价不变
+			let price = self.current_ticket_price();
+			let amount = value / price;
+			// 确保剩余票数够
+			let balance_tokens = self
+				.balance_tokens
+				.checked_sub(amount)
+				.ok_or(VotingError::SoldOut)?;
+			// 用户ticket增加
+			let voter_balance = self
+				.voter_ticket_balance(owner)
+				.checked_add(amount)
+				.ok_or(VotingError::Overflow)?;
+			if voter_balance > self.max_tickets_per_voter {
+				return Err(VotingError::ExceedsTicketCap);
+			}
+			// 记录不足一张票的零头
+			let dust = value % price;
+			let total_dust = self
+				.refund_dust
+				.get(&owner)
+				.unwrap_or(&0)
+				.checked_add(dust)
+				.ok_or(VotingError::Overflow)?;
+
+			self.voter_balance.insert(owner, voter_balance);
+			// balance_tokens减少
+			self.balance_tokens = balance_tokens;
+			if total_dust > 0 {
+				self.refund_dust.insert(owner, total_dust);
+			}
+			if amount > 0 {
+				self.has_purchased.insert(owner, ());
+			}
+
+			Ok(())
+		}
+		// 质押stake_token换票, 按当前票价换算, 只从调用者账户转走整数张票对应的代币
+		// 调用前需要先在代币合约中授权本合约转走这些代币
+		#[ink(message)]
+		pub fn stake_for_tickets(&mut self, amount: Balance) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Registration)?;
+			let owner = self.env().caller();
+			self.ensure_not_delegated(owner)?;
+			let token = self.stake_token.ok_or(VotingError::StakingDisabled)?;
+			// 质押时没有默克尔证明, 开启白名单时不能质押
+			if self.whitelist_enabled {
+				return Err(VotingError::NotWhitelisted);
+			}
+			let price = self.current_ticket_price();
+			let tickets = amount / price;
+			if tickets == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			let cost = tickets * price;
+			let balance_tokens = self
+				.balance_tokens
+				.checked_sub(tickets)
+				.ok_or(VotingError::SoldOut)?;
+			let voter_balance = self
+				.voter_ticket_balance(owner)
+				.checked_add(tickets)
+				.ok_or(VotingError::Overflow)?;
+			if voter_balance > self.max_tickets_per_voter {
+				return Err(VotingError::ExceedsTicketCap);
+			}
+			let staked = self
+				.staked_of(owner)
+				.checked_add(cost)
+				.ok_or(VotingError::Overflow)?;
+			let staked_tickets = self
+				.staked_tickets_of(owner)
+				.checked_add(tickets)
+				.ok_or(VotingError::Overflow)?;
+			Self::token_transfer_from(token, owner, self.env().account_id(), cost)?;
+
+			self.voter_balance.insert(owner, voter_balance);
+			self.balance_tokens = balance_tokens;
+			self.staked_amount.insert(owner, staked);
+			self.staked_tickets.insert(owner, staked_tickets);
+			self.has_purchased.insert(owner, ());
+			Ok(())
+		}
+		// 取回调用者质押的全部代币, 返回取回的数量
+		// 只能在投票结束后调用, 质押换到的票必须已经全部投出, 结束后投出的票不能再撤回
+		#[ink(message)]
+		pub fn unstake(&mut self) -> Result<Balance> {
+			let owner = self.env().caller();
+			let token = self.stake_token.ok_or(VotingError::StakingDisabled)?;
+			if !self.stopped && !self.has_ended() && self.phase != VotingPhase::Ended {
+				return Err(VotingError::WrongPhase);
+			}
+			let staked = self.staked_of(owner);
+			if staked == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			// 紧急停止后未投出的质押票已作废, 可以直接取回
+			if !self.stopped && self.staked_tickets_of(owner) > 0 {
+				return Err(VotingError::UncastTickets);
+			}
+			Self::token_transfer(token, owner, staked)?;
+			self.staked_amount.take(&owner);
+			self.staked_tickets.take(&owner);
+			self.staked_tickets_cast.take(&owner);
+			Ok(staked)
+		}
+		// 某投票人质押的代币数量
+		#[ink(message)]
+		pub fn staked_of(&self, owner: AccountId) -> Balance {
+			*self.staked_amount.get(&owner).unwrap_or(&0)
+		}
+		// 某投票人质押换到且还没投出的票数, 这些票不能转让, 委托或退回
+		#[ink(message)]
+		pub fn staked_tickets_of(&self, owner: AccountId) -> u128 {
+			*self.staked_tickets.get(&owner).unwrap_or(&0)
+		}
+		// 批量买票, 依次给每个用户买票, 返回每一项的结果
+		// 某一项失败不影响其他项, 开启require_payment时所有项共用一笔转账
+		#[ink(message, payable)]
+		pub fn batch_buy_tickets(&mut self, purchases: Vec<(AccountId, u128)>) -> Vec<Result<()>> {
+			let mut results = Vec::new();
+			let mut total_bought: u128 = 0;
+			let mut paid = self.env().transferred_balance();
+			for (owner, value) in purchases.into_iter() {
+				let balance_tokens = self.balance_tokens;
+				let ret = self.buy_ticket_paid(owner, value, None, &mut paid);
+				if ret.is_ok() {
+					total_bought =
+						total_bought.saturating_add(balance_tokens - self.balance_tokens);
+				}
+				results.push(ret);
+			}
+			// 多个购票人共用一笔转账, 剩余的金额退回给付款的调用者
+			self.refund_payment(self.env().caller(), paid);
+			self.env().emit_event(BatchPurchase {
+				total_bought,
+				election_id: self.election_id,
+			});
+			results
+		}
+		// 转让选票, 只能由from本人调用
+		#[ink(message)]
+		pub fn transfer_tickets(
+			&mut self,
+			from: AccountId,
+			to: AccountId,
+			amount: u128,
+		) -> Result<()> {
+			self.ensure_not_stopped()?;
+			if self.env().caller() != from {
+				return Err(VotingError::Unauthorized);
+			}
+			if amount == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			let from_balance = self
+				.voter_ticket_balance(from)
+				.checked_sub(amount)
+				.ok_or(VotingError::InsufficientTickets)?;
+			if from_balance < self.staked_tickets_of(from) {
+				return Err(VotingError::StakedTicketsLocked);
+			}
+			self.voter_balance.insert(from, from_balance);
+			// 总票数有上限, to的票数不会溢出
+			let to_balance = self
+				.voter_ticket_balance(to)
+				.checked_add(amount)
+				.ok_or(VotingError::Overflow)?;
+			self.voter_balance.insert(to, to_balance);
+			self.env().emit_event(TicketTransferred {
+				from,
+				to,
+				amount,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+		// 把from剩余的票全部委托给delegate, 只能由from本人调用
+		// 委托后from不能再买票和投票
+		// 如果delegate也委托了他人, 票会沿着委托链转给最终的受托人
+		#[ink(message)]
+		pub fn delegate_vote(&mut self, from: AccountId, delegate: AccountId) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			if self.env().caller() != from {
+				return Err(VotingError::Unauthorized);
+			}
+			self.ensure_not_delegated(from)?;
+			if self.staked_tickets_of(from) > 0 {
+				return Err(VotingError::StakedTicketsLocked);
+			}
+			// 沿着委托链找到最终的受托人, 途中遇到from说明形成了环
+			let mut to = delegate;
+			loop {
+				if to == from {
+					return Err(VotingError::CircularDelegation);
+				}
+				match self.delegations.get(&to) {
+					Some(next) => to = *next,
+					None => break,
+				}
+			}
+			let amount = self.voter_ticket_balance(from);
+			let to_balance = self
+				.voter_ticket_balance(to)
+				.checked_add(amount)
+				.ok_or(VotingError::Overflow)?;
+			self.voter_balance.take(&from);
+			self.voter_balance.insert(to, to_balance);
+			self.delegations.insert(from, delegate);
+			self.env().emit_event(VoteDelegated {
+				from,
+				delegate,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+		// 某用户把投票权委托给了谁
+		#[ink(message)]
+		pub fn delegate_of(&self, voter: AccountId) -> Option<AccountId> {
+			self.delegations.get(&voter).copied()
+		}
+		// 领回调用者累计的零头, 返回领回的数量
+		#[ink(message)]
+		pub fn claim_dust(&mut self) -> u128 {
+			let caller = self.env().caller();
+			let dust = self.refund_dust.take(&caller).unwrap_or(0);
+			// 开启require_payment时转出原生代币, 转账失败时保留待领金额
+			if self.require_payment && dust > 0 && self.env().transfer(caller, dust).is_err() {
+				self.refund_dust.insert(caller, dust);
+				return 0;
+			}
+			dust
+		}
+		// 退回手中的票, 只能由owner本人在投票截止前调用
+		// 退回的票回到剩余票数中, 按当前票价记入可领回的金额
+		#[ink(message)]
+		pub fn refund_tickets(&mut self, owner: AccountId, amount: u128) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			if amount == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			let voter_balance = self
+				.voter_ticket_balance(owner)
+				.checked_sub(amount)
+				.ok_or(VotingError::InsufficientTickets)?;
+			if voter_balance < self.staked_tickets_of(owner) {
+				return Err(VotingError::StakedTicketsLocked);
+			}
+			let balance_tokens = self
+				.balance_tokens
+				.checked_add(amount)
+				.ok_or(VotingError::Overflow)?;
+			// 开启require_payment时按实际支付的平均票价退款, 否则按当前基础票价记账
+			let (refund, payment) = if self.require_payment {
+				let (refund, payment) = self.paid_refund(owner, amount);
+				(Some(refund), Some(payment))
+			} else {
+				(amount.checked_mul(self.token_price), None)
+			};
+			let credit = refund
+				.and_then(|x| x.checked_add(*self.refund_credits.get(&owner).unwrap_or(&0)))
+				.ok_or(VotingError::Overflow)?;
+
+			self.voter_balance.insert(owner, voter_balance);
+			self.balance_tokens = balance_tokens;
+			self.refund_credits.insert(owner, credit);
+			if let Some(payment) = payment {
+				self.update_ticket_payment(owner, payment);
+			}
+			Ok(())
+		}
+		// 领回调用者退票累计的金额, 返回领回的数量
+		#[ink(message)]
+		pub fn claim_refund(&mut self) -> u128 {
+			let caller = self.env().caller();
+			let credit = self.refund_credits.take(&caller).unwrap_or(0);
+			// 转账失败时放回待领金额, 之后可以再次领取
+			if self.require_payment && credit > 0 && self.env().transfer(caller, credit).is_err() {
+				self.refund_credits.insert(caller, credit);
+				return 0;
+			}
+			credit
+		}
+		// 投票是否已截止
+		#[ink(message)]
+		pub fn has_ended(&self) -> bool {
+			self.env().block_timestamp() > self.deadline
+		}
+		// 剩余票数
+		#[ink(message)]
+		pub fn all_ticket_num(&mut self) -> u128 {
+			self.total_tokens
+		}
+		// 剩余票数
+		#[ink(message)]
+		pub fn left_ticket_num(&mut self) -> u128 {
+			self.balance_tokens
+		}
+		// 计票方式
+		#[ink(message)]
+		pub fn voting_mode(&self) -> VotingMode {
+			self.mode
+		}
+		// 增发选票, 仅管理员可调用, 增发后总票数不能超过max_supply
+		#[ink(message)]
+		pub fn mint_tokens(&mut self, amount: u128) -> Result<()> {
+			self.ensure_admin()?;
+			let new_total = self
+				.total_tokens
+				.checked_add(amount)
+				.filter(|x| *x <= self.max_supply)
+				.ok_or(VotingError::MaxSupplyExceeded)?;
+			// 剩余票数不会超过总票数, 不会溢出
+			self.balance_tokens += amount;
+			self.total_tokens = new_total;
+			self.env().emit_event(TokensMinted {
+				amount,
+				new_total,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+		// 每个投票人最多能买的票数
+		#[ink(message)]
+		pub fn get_max_tickets_per_voter(&self) -> u128 {
+			self.max_tickets_per_voter
+		}
+		// 当前购买一票需要的价格
+		#[ink(message)]
+		pub fn price_of_ticket(&mut self) -> Balance {
+			self.current_ticket_price()
+		}
+		// 内部辅助函数计算当前票价, 设置了auction_step时票价随卖出的票数上涨
+		// token_price + 已卖出票数 / auction_step
+		fn current_ticket_price(&self) -> Balance {
+			if self.auction_step == 0 {
+				return self.token_price;
+			}
+			let sold = self.total_tokens - self.balance_tokens;
+			self.token_price.saturating_add(sold / self.auction_step)
+		}
+		// 修改票价, 仅管理员可调用, 新票价不能低于部署时设置的下限
+		#[ink(message)]
+		pub fn set_token_price(&mut self, new_price: u128) -> Result<()> {
+			self.ensure_admin()?;
+			if new_price < self.min_price {
+				return Err(VotingError::PriceBelowMinimum);
+			}
+			let old_price = self.token_price;
+			self.token_price = new_price;
+			self.env().emit_event(TokenPriceChanged {
+				old_price,
+				new_price,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+		// 某用户手中的票数
+		#[ink(message)]
+		pub fn voter_ticket_balance(&mut self, owner: AccountId) -> u128 {
+			*self.voter_balance.get(&owner).unwrap_or(&0)
+		}
+		// 获取可被投票的用户数量
+		#[ink(message)]
+		pub fn get_candidates_len(&mut self) -> u32 {
+			self.candidate_list.len()
+		}
+		// 某用户是否在候选人列表中
+		#[ink(message)]
+		pub fn is_candidate(&self, account: AccountId) -> bool {
+			self.in_candidate_list.contains_key(&account)
+		}
+		// 获取可被投票的用户
+		#[ink(message)]
+		pub fn get_candidates(&mut self) -> Vec<AccountId> {
+			self.candidate_list.iter().copied().collect()
+		}
+		// 获取当前各用户投票票数状态
+		#[ink(message)]
+		pub fn get_current_votes(&mut self) -> Vec<VoteOfCandidate> {
+			let candidate_list: Vec<_> = self.candidate_list.iter().copied().collect();
+			let mut current_vote: Vec<VoteOfCandidate> = Vec::new();
+			for x in candidate_list.clone().into_iter() {
+				let candidate = VoteOfCandidate {
+					candidate: x,
+					vote: self.my_value_or_zero(x),
+				};
+				current_vote.push(candidate);
+			}
+			current_vote
+		}
+		// 获取第index个可被投票的用户, 从0开始, 越界时返回None
+		#[ink(message)]
+		pub fn get_candidate_at_index(&self, index: u32) -> Option<AccountId> {
+			self.candidate_list.get(index).copied()
+		}
+		// 分页获取可被投票的用户, 从第offset个开始最多返回limit个
+		#[ink(message)]
+		pub fn get_candidates_paginated(&mut self, offset: u32, limit: u32) -> Vec<AccountId> {
+			self.candidate_list
+				.iter()
+				.skip(offset as usize)
+				.take(limit as usize)
+				.copied()
+				.collect()
+		}
+		// 分页获取各用户投票票数状态, 从第offset个开始最多返回limit个
+		#[ink(message)]
+		pub fn get_votes_paginated(&mut self, offset: u32, limit: u32) -> Vec<VoteOfCandidate> {
+			self.get_candidates_paginated(offset, limit)
+				.into_iter()
+				.map(|x| VoteOfCandidate {
+					candidate: x,
+					vote: self.my_value_or_zero(x),
+				})
+				.collect()
+		}
+		// 获取选举概况
+		#[ink(message)]
+		pub fn get_election_summary(&mut self) -> ElectionSummary {
+			ElectionSummary {
+				total_tokens: self.all_ticket_num(),
+				balance_tokens: self.left_ticket_num(),
+				candidate_count: self.get_candidates_len(),
+				votes: self.get_current_votes(),
+				winner: self.get_winner().unwrap_or(None),
+			}
+		}
+		// 导出合约状态, 返回scale编码后的StateSnapshot
+		// 候选人按加入顺序排列, 相同状态的输出完全相同
+		#[ink(message)]
+		pub fn export_state(&self) -> Vec<u8> {
+			let candidates: Vec<AccountId> = self.candidate_list.iter().copied().collect();
+			let votes = candidates
+				.iter()
+				.map(|x| self.my_value_or_zero(*x))
+				.collect();
+			scale::Encode::encode(&StateSnapshot {
+				candidates,
+				votes,
+				total_tokens: self.total_tokens,
+				balance_tokens: self.balance_tokens,
+				block_number: self.env().block_number(),
+			})
+		}
+		// 从export_state导出的快照恢复状态, 仅管理员可在还没有人买票和投票时调用
+		// 候选人列表, 票数, 总票数和剩余票数都会被快照中的数据覆盖
+		#[ink(message)]
+		pub fn import_state(&mut self, snapshot: StateSnapshot) -> Result<()> {
+			self.ensure_admin()?;
+			self.ensure_candidate_list_unlocked()?;
+			if !self.votes_received.is_empty() || self.balance_tokens != self.total_tokens {
+				return Err(VotingError::VotesAlreadyCast);
+			}
+			if snapshot.candidates.len() != snapshot.votes.len() {
+				return Err(VotingError::InvalidSnapshot);
+			}
+			if snapshot.balance_tokens > snapshot.total_tokens {
+				return Err(VotingError::TicketPoolExceedsTotal);
+			}
+			if snapshot.total_tokens > self.max_supply {
+				return Err(VotingError::MaxSupplyExceeded);
+			}
+			for (i, x) in snapshot.candidates.iter().enumerate() {
+				if snapshot.candidates[..i].contains(x) {
+					return Err(VotingError::DuplicateCandidate);
+				}
+			}
+			let candidates: Vec<_> = self.candidate_list.iter().copied().collect();
+			for x in candidates {
+				self.take_candidate(x);
+			}
+			for (candidate, votes) in snapshot.candidates.into_iter().zip(snapshot.votes) {
+				self.insert_candidate(candidate);
+				if votes > 0 {
+					self.total_votes_cast = self.total_votes_cast.saturating_add(votes);
+					self.votes_received.insert(candidate, votes);
+				}
+			}
+			// 快照中已卖出的票都视为已花费
+			self.spent_tickets = snapshot.total_tokens - snapshot.balance_tokens;
+			self.total_tokens = snapshot.total_tokens;
+			self.balance_tokens = snapshot.balance_tokens;
+			self.env().emit_event(StateImported {
+				block_number: snapshot.block_number,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+		// 获取投票率, 即已投出的票占总票数的比例, 单位为万分之一(bps)
+		// 已投出的票 = 总票数 - 剩余票数 - 投票人手中还没投出的票
+		#[ink(message)]
+		pub fn get_turnout_bps(&self) -> u32 {
+			if self.total_tokens == 0 {
+				return 0;
+			}
+			let unspent = self
+				.voter_balance
+				.values()
+				.fold(0u128, |sum, x| sum.saturating_add(*x));
+			let spent = self
+				.total_tokens
+				.saturating_sub(self.balance_tokens)
+				.saturating_sub(unspent);
+			(spent.saturating_mul(10_000) / self.total_tokens) as u32
+		}
+		// 登记有投票资格的用户, 仅管理员可调用, 用于统计参与率
+		#[ink(message)]
+		pub fn register_voter(&mut self, voter: AccountId) -> Result<()> {
+			self.ensure_admin()?;
+			self.registered_voters.insert(voter, ());
+			Ok(())
+		}
+		// 获取参与率, 即登记的用户中买过票的比例, 单位为万分之一(bps)
+		// 没有登记用户时为0, 没有登记的买票用户不计入
+		#[ink(message)]
+		pub fn get_participation_rate_bps(&self) -> u32 {
+			let registered = self.registered_voters.len();
+			if registered == 0 {
+				return 0;
+			}
+			let purchased = self
+				.registered_voters
+				.keys()
+				.filter(|x| self.has_purchased.contains_key(*x))
+				.count() as u64;
+			(purchased * 10_000 / u64::from(registered)) as u32
+		}
+		// 检查合约内部数据是否一致, 返回发现的问题, 为空时数据正常
+		// 平方投票, 移除候选人等情况下花费的票多于候选人的票数, 所以按spent_tickets而不是候选人的票数核对
+		#[ink(message)]
+		pub fn verify_election_integrity(&self) -> Vec<VotingError> {
+			let mut violations = Vec::new();
+			if self.in_candidate_list.len() != self.candidate_list.len() {
+				violations.push(VotingError::CandidateIndexMismatch);
+			}
+			if self.balance_tokens > self.total_tokens {
+				violations.push(VotingError::TicketPoolExceedsTotal);
+			}
+			let held = self
+				.voter_balance
+				.values()
+				.fold(0u128, |sum, x| sum.saturating_add(*x));
+			let unclaimed = self
+				.emergency_refunds
+				.values()
+				.fold(0u128, |sum, x| sum.saturating_add(*x));
+			let accounted = held
+				.saturating_add(self.spent_tickets)
+				.saturating_add(self.total_abstentions)
+				.saturating_add(unclaimed)
+				.saturating_add(self.balance_tokens);
+			if accounted != self.total_tokens {
+				violations.push(VotingError::TicketSupplyMismatch);
+			}
+			if self
+				.vote_num
+				.keys()
+				.any(|(_, candidate)| !self.in_candidate_list.contains_key(candidate))
+			{
+				violations.push(VotingError::OrphanedVote);
+			}
+			violations
+		}
+		// 获取候选人衰减后的有效票数, votes_received中仍保存未衰减的票数
+		// 每个投票人投的票从最后一次投给该候选人的区块开始按decay_rate_bps_per_block复利衰减
+		// 按各投票人衰减后的比例折算候选人的得票, 所以投票人权重和计票方式的影响也会保留
+		#[ink(message)]
+		pub fn get_effective_votes_for(&self, candidate: AccountId) -> u128 {
+			let votes = self.my_value_or_zero(candidate);
+			if self.decay_rate_bps_per_block == 0 || votes == 0 {
+				return votes;
+			}
+			let now = self.env().block_number();
+			let mut raw: u128 = 0;
+			let mut decayed: u128 = 0;
+			for (pair, amount) in self.vote_num.iter().filter(|((_, x), _)| *x == candidate) {
+				let voted_at = *self.vote_timestamps.get(pair).unwrap_or(&now);
+				let factor = self.decay_factor_bps(now.saturating_sub(voted_at));
+				raw = raw.saturating_add(*amount);
+				decayed = decayed.saturating_add(amount.saturating_mul(factor) / 10_000);
+			}
+			if raw == 0 {
+				return votes;
+			}
+			match votes.checked_mul(decayed) {
+				Some(x) => x / raw,
+				None => votes / raw * decayed,
+			}
+		}
+		// 获取fee_treasury收取的手续费票数
+		#[ink(message)]
+		pub fn get_treasury_balance(&self) -> u128 {
+			*self.treasury_votes.get(&self.fee_treasury).unwrap_or(&0)
+		}
+		// 获取剩余的票全部卖出并投给candidate时该候选人能达到的票数, 不是候选人时为0
+		// 只是理论上限, 不考虑权重, 计票方式和投票人手中未投出的票
+		#[ink(message)]
+		pub fn get_max_achievable_votes_for(&self, candidate: AccountId) -> u128 {
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return 0;
+			}
+			self.my_value_or_zero(candidate)
+				.saturating_add(self.balance_tokens)
+		}
+		// 按strategy建议投票人如何分配手中剩余的票, 返回(候选人, 票数), 票数之和等于手中的票
+		// 按线性计票计算, 不考虑每人投给同一候选人的票数上限, 没有票或没有候选人时返回空列表
+		#[ink(message)]
+		pub fn suggest_allocation(
+			&self,
+			voter: AccountId,
+			strategy: AllocationStrategy,
+		) -> Vec<(AccountId, u128)> {
+			let balance = *self.voter_balance.get(&voter).unwrap_or(&0);
+			let candidates: Vec<AccountId> = self.candidate_list.iter().copied().collect();
+			if balance == 0 || candidates.is_empty() {
+				return Vec::new();
+			}
+			let target = match strategy {
+				// max_by_key平票时返回最后一个, 所以倒序查找
+				AllocationStrategy::MaximizeLeader => candidates
+					.iter()
+					.rev()
+					.max_by_key(|x| self.my_value_or_zero(**x)),
+				AllocationStrategy::BoostUnderdog => {
+					candidates.iter().min_by_key(|x| self.my_value_or_zero(**x))
+				}
+				AllocationStrategy::Spread => {
+					let count = candidates.len() as u128;
+					let share = balance / count;
+					let extra = (balance % count) as usize;
+					return candidates
+						.into_iter()
+						.enumerate()
+						.map(|(i, x)| (x, share + if i < extra { 1 } else { 0 }))
+						.filter(|(_, amount)| *amount > 0)
+						.collect();
+				}
+			};
+			target
+				.map(|x| ink_prelude::vec![(*x, balance)])
+				.unwrap_or_default()
+		}
+		// 获取票数在[min_votes, max_votes]之间的候选人, 按候选人列表的顺序排列
+		// min_votes大于max_votes时返回空列表
+		#[ink(message)]
+		pub fn get_candidates_by_vote_range(
+			&self,
+			min_votes: u128,
+			max_votes: u128,
+		) -> Vec<AccountId> {
+			self.candidate_list
+				.iter()
+				.copied()
+				.filter(|x| (min_votes..=max_votes).contains(&self.my_value_or_zero(*x)))
+				.collect()
+		}
+		// 获取第一名与第二名的票数差, 并列第一时为0, 候选人少于两个时返回None
+		#[ink(message)]
+		pub fn get_largest_gap(&self) -> Option<u128> {
+			if self.candidate_list.len() < 2 {
+				return None;
+			}
+			let (first, second) = self.top_two_votes();
+			Some(first - second)
+		}
+		// 获取第一名领先第二名的票数占总票数的比例, 单位为万分之一(bps)
+		// 这只是归一化后的领先幅度, 不是统计意义上的置信区间, 没有考虑投票人数的多少
+		// 例如只有1票时结果也是10000, 需要结合总票数一起判断领先是否可靠
+		// 还没有人得票时为0, 只有一个候选人时第二名按0票计算
+		#[ink(message)]
+		pub fn get_confidence_margin_bps(&self) -> u32 {
+			let total = self.total_candidate_votes();
+			if total == 0 {
+				return 0;
+			}
+			let (first, second) = self.top_two_votes();
+			let gap = first - second;
+			// gap不超过total, 乘法溢出时total / 10000一定大于0
+			let margin = match gap.checked_mul(10_000) {
+				Some(x) => x / total,
+				None => gap / (total / 10_000),
+			};
+			margin.min(10_000) as u32
+		}
+		// 获取各候选人得票的基尼系数, 单位为万分之一(bps)
+		// G = sum_i sum_j |vi - vj| / (2 * n * sum_i vi), 票数平均分布时为0, 全部集中在一个候选人时为10000 * (n - 1) / n
+		// 候选人少于两个或还没有人得票时为0
+		#[ink(message)]
+		pub fn get_gini_coefficient_bps(&self) -> u32 {
+			let n = self.candidate_list.len();
+			let total = self.total_candidate_votes();
+			if n < 2 || total == 0 {
+				return 0;
+			}
+			// 总票数超过2^64时先把票数缩小, 保证下面的乘法不会溢出
+			let shift = 64u32.saturating_sub(total.leading_zeros());
+			let mut votes: Vec<u128> = self
+				.candidate_list
+				.iter()
+				.map(|x| self.my_value_or_zero(*x) >> shift)
+				.collect();
+			votes.sort_unstable();
+			let total: u128 = votes.iter().sum();
+			// 按从小到大排序后, sum_i sum_j |vi - vj| = 2 * sum_i (2i + 1 - n) * vi
+			let n = u128::from(n);
+			let mut positive: u128 = 0;
+			let mut negative: u128 = 0;
+			for (i, v) in votes.iter().enumerate() {
+				let k = 2 * i as u128 + 1;
+				if k >= n {
+					positive += (k - n) * v;
+				} else {
+					negative += (n - k) * v;
+				}
+			}
+			((positive - negative) * 10_000 / (n * total)) as u32
+		}
+		// 获取票数分布的香农熵, 按log2(候选人数量)归一化, 单位为万分之一(bps)
+		// 票数平均分布时为10000, 全部集中在一个候选人时为0
+		// 候选人少于两个或还没有人得票时为0
+		#[ink(message)]
+		pub fn get_vote_distribution_entropy_bps(&self) -> u32 {
+			let n = self.candidate_list.len();
+			let total = self.total_candidate_votes();
+			if n < 2 || total == 0 {
+				return 0;
+			}
+			// H = log2(total) - sum(v * log2(v)) / total
+			let weighted = self
+				.candidate_list
+				.iter()
+				.map(|x| self.my_value_or_zero(*x))
+				.filter(|v| *v > 0)
+				.fold(0u128, |sum, v| {
+					sum.saturating_add(v.saturating_mul(Self::log2_fixed(v)))
+				});
+			let entropy = Self::log2_fixed(total).saturating_sub(weighted / total);
+			let bps = entropy.saturating_mul(10_000) / Self::log2_fixed(u128::from(n));
+			bps.min(10_000) as u32
+		}
+		// 获取各候选人的得票占比, 单位为万分之一(bps)
+		// 结果向下取整, 总和不超过10000, 还没有人得票时全部为0
+		#[ink(message)]
+		pub fn get_vote_share_bps(&self) -> Vec<(AccountId, u32)> {
+			let total = self.candidate_list.iter().fold(0u128, |sum, x| {
+				sum.saturating_add(self.my_value_or_zero(*x))
+			});
+			self.candidate_list
+				.iter()
+				.map(|x| {
+					let share = if total == 0 {
+						0
+					} else {
+						self.my_value_or_zero(*x).saturating_mul(10_000) / total
+					};
+					(*x, share as u32)
+				})
+				.collect()
+		}
+		// 获取得票最多的前n个候选人, 按票数从高到低排序, 平票时按加入顺序排列
+		// n超过候选人数量时返回全部候选人, 票数低于min_votes_threshold的候选人不计入
+		#[ink(message)]
+		pub fn get_top_n_candidates(&mut self, n: u32) -> Vec<VoteOfCandidate> {
+			let n = core::cmp::min(n, self.candidate_list.len()) as usize;
+			let threshold = self.min_votes_threshold;
+			let mut ranked: Vec<(usize, VoteOfCandidate)> = self
+				.get_current_votes()
+				.into_iter()
+				.enumerate()
+				.filter(|(_, x)| x.vote >= threshold)
+				.collect();
+			ranked.sort_unstable_by(|(i, a), (j, b)| b.vote.cmp(&a.vote).then(i.cmp(j)));
+			ranked.into_iter().take(n).map(|(_, x)| x).collect()
+		}
+
+		// 获取某候选人当前的名次, 1为得票最多
+		// 名次为得票严格多于该候选人的人数加1, 平票的候选人名次相同
+		// 不是候选人时返回None
+		#[ink(message)]
+		pub fn get_candidate_rank(&self, candidate: AccountId) -> Option<u32> {
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return None;
+			}
+			let votes = self.my_value_or_zero(candidate);
+			let ahead = self
+				.candidate_list
+				.iter()
+				.filter(|x| self.my_value_or_zero(**x) > votes)
+				.count() as u32;
+			Some(ahead + 1)
+		}
+
+		// 获取票数不低于min_votes_threshold的候选人, 按加入顺序排列
+		#[ink(message)]
+		pub fn get_qualified_candidates(&self) -> Vec<AccountId> {
+			self.candidate_list
+				.iter()
+				.copied()
+				.filter(|x| self.my_value_or_zero(*x) >= self.min_votes_threshold)
+				.collect()
+		}
+
+		// 投票
+		// owner 投票人
+		// candidate 被投票人
+		// amout 投票数量
+		// 只能由owner本人调用, 代理投票通过vote_as_proxy或VoteProxy::proxy_vote
+		#[ink(message)]
+		pub fn vote_candidate(
+			&mut self,
+			owner: AccountId,
+			candidate: AccountId,
+			amout: u128,
+		) -> Result<()> {
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			self.cast_vote(owner, candidate, amout)
+		}
+
+		// 内部辅助函数投票并触发事件, 调用前需要确认调用者有权替owner投票
+		fn cast_vote(&mut self, owner: AccountId, candidate: AccountId, amout: u128) -> Result<()> {
+			self.vote_candidate_without_event(owner, candidate, amout)?;
+			self.env().emit_event(VoteCast {
+				from: owner,
+				to: candidate,
+				amount: amout,
+				voter_remaining: self.voter_ticket_balance(owner),
+				candidate_total: self.my_value_or_zero(candidate),
+				block_number: self.env().block_number(),
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 授权proxy代替调用者投票, 最多投amount票, 会覆盖之前的额度, 为0时取消授权
+		#[ink(message)]
+		pub fn grant_proxy_allowance(&mut self, proxy: AccountId, amount: u128) -> Result<()> {
+			self.ensure_not_stopped()?;
+			let owner = self.env().caller();
+			if amount == 0 {
+				self.proxy_allowances.take(&(owner, proxy));
+			} else {
+				self.proxy_allowances.insert((owner, proxy), amount);
+			}
+			Ok(())
+		}
+
+		// 获取proxy代替owner投票的剩余额度
+		#[ink(message)]
+		pub fn get_proxy_allowance(&self, owner: AccountId, proxy: AccountId) -> u128 {
+			*self.proxy_allowances.get(&(owner, proxy)).unwrap_or(&0)
+		}
+
+		// 调用者使用owner授予的额度代替owner投票, 投票成功后扣除额度
+		#[ink(message)]
+		pub fn vote_as_proxy(
+			&mut self,
+			owner: AccountId,
+			candidate: AccountId,
+			amount: u128,
+		) -> Result<()> {
+			let key = (owner, self.env().caller());
+			let allowance = *self.proxy_allowances.get(&key).unwrap_or(&0);
+			if allowance < amount {
+				return Err(VotingError::InsufficientAllowance);
+			}
+			self.cast_vote(owner, candidate, amount)?;
+			if allowance == amount {
+				self.proxy_allowances.take(&key);
+			} else {
+				self.proxy_allowances.insert(key, allowance - amount);
+			}
+			Ok(())
+		}
+
+		// 为投给candidate的票投保, 只能由owner本人调用, 会覆盖之前投保的票数
+		// 候选人退出选举时投保的票退回投票人手中, 投保的票数不能超过已投给该候选人的票数
+		#[ink(message)]
+		pub fn insure_vote(
+			&mut self,
+			owner: AccountId,
+			candidate: AccountId,
+			tickets_insured: u128,
+		) -> Result<()> {
+			self.ensure_not_stopped()?;
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return Err(VotingError::NotACandidate);
+			}
+			if tickets_insured == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			if tickets_insured > self.callee_vote_of(owner, candidate) {
+				return Err(VotingError::InsufficientVotes);
+			}
+			self.insurance.insert((owner, candidate), tickets_insured);
+			Ok(())
+		}
+
+		// 获取owner为投给candidate的票投保的票数
+		#[ink(message)]
+		pub fn get_insured_votes(&self, owner: AccountId, candidate: AccountId) -> u128 {
+			*self.insurance.get(&(owner, candidate)).unwrap_or(&0)
+		}
+
+		// 候选人把自己的部分得票转给另一个候选人, 只能由from_candidate本人在投票阶段调用
+		// 只改变两个候选人的得票, 投票人的票数和投票记录都不变
+		#[ink(message)]
+		pub fn transfer_votes(
+			&mut self,
+			from_candidate: AccountId,
+			to_candidate: AccountId,
+			amount: u128,
+		) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			if self.env().caller() != from_candidate {
+				return Err(VotingError::Unauthorized);
+			}
+			if !self.in_candidate_list.contains_key(&from_candidate)
+				|| !self.in_candidate_list.contains_key(&to_candidate)
+			{
+				return Err(VotingError::NotACandidate);
+			}
+			if amount == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			let from_votes = self
+				.my_value_or_zero(from_candidate)
+				.checked_sub(amount)
+				.ok_or(VotingError::InsufficientVotes)?;
+			if from_candidate != to_candidate {
+				let to_votes = self
+					.my_value_or_zero(to_candidate)
+					.checked_add(amount)
+					.ok_or(VotingError::Overflow)?;
+				if to_votes > self.max_votes_per_candidate {
+					return Err(VotingError::CandidateCapExceeded);
+				}
+				// 转移不改变总票数, 只检查接收方的得票占比
+				self.ensure_vote_share(to_candidate, to_votes)?;
+				self.votes_received.insert(from_candidate, from_votes);
+				self.votes_received.insert(to_candidate, to_votes);
+			}
+			self.env().emit_event(VotesTransferred {
+				from: from_candidate,
+				to: to_candidate,
+				amount,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 按持有时间更新voter投给candidate的票的信念倍数, 任何人都可以调用, 返回这些票的有效票数
+		// 倍数为min(持有区块数 / conviction_period, max_conviction_multiplier), 至少为1
+		// 持有时间从最后一次投给该候选人时算起, 再次投票会重新计时
+		#[ink(message)]
+		pub fn apply_conviction(&mut self, voter: AccountId, candidate: AccountId) -> Result<u128> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			if self.conviction_period == 0 {
+				return Err(VotingError::ConvictionDisabled);
+			}
+			let amount = self.callee_vote_of(voter, candidate);
+			if amount == 0 {
+				return Err(VotingError::InsufficientVotes);
+			}
+			let committed_at = *self
+				.vote_timestamps
+				.get(&(voter, candidate))
+				.unwrap_or(&self.env().block_number());
+			let blocks_held = self.env().block_number().saturating_sub(committed_at);
+			let multiplier = u128::from(blocks_held / self.conviction_period)
+				.min(self.max_conviction_multiplier)
+				.max(1);
+			let effective = amount
+				.checked_mul(multiplier)
+				.ok_or(VotingError::Overflow)?;
+			// 只把和上次相比新增或减少的部分计入候选人
+			let bonus = effective - amount;
+			let old_bonus = *self.conviction_bonus.get(&(voter, candidate)).unwrap_or(&0);
+			let votes_received = self
+				.my_value_or_zero(candidate)
+				.checked_add(bonus)
+				.and_then(|x| x.checked_sub(old_bonus))
+				.ok_or(VotingError::Overflow)?;
+			if bonus > old_bonus && votes_received > self.max_votes_per_candidate {
+				return Err(VotingError::CandidateCapExceeded);
+			}
+			self.total_votes_cast = self
+				.total_votes_cast
+				.saturating_add(bonus)
+				.saturating_sub(old_bonus);
+			self.votes_received.insert(candidate, votes_received);
+			self.conviction_bonus.insert((voter, candidate), bonus);
+			Ok(effective)
+		}
+
+		// 投票人手中还有票且距离截止时间少于warning_window_ms时发出提醒事件, 任何人都可以调用
+		// 返回是否发出了提醒
+		#[ink(message)]
+		pub fn notify_expiring_tickets(&mut self, voter: AccountId) -> bool {
+			let balance = self.voter_ticket_balance(voter);
+			if balance == 0 || self.has_ended() {
+				return false;
+			}
+			let ms_remaining = self.deadline - self.env().block_timestamp();
+			if ms_remaining >= self.warning_window_ms {
+				return false;
+			}
+			self.env().emit_event(TicketsAboutToExpire {
+				voter,
+				balance,
+				ms_remaining,
+				election_id: self.election_id,
+			});
+			true
+		}
+
+		// 条件投票, preferred的票数不少于其他所有候选人时投给preferred, 否则投给fallback
+		// 返回实际投票的候选人
+		#[ink(message)]
+		pub fn vote_candidate_if_leading(
+			&mut self,
+			owner: AccountId,
+			preferred: AccountId,
+			fallback: AccountId,
+			amount: u128,
+		) -> Result<AccountId> {
+			if !self.in_candidate_list.contains_key(&preferred)
+				|| !self.in_candidate_list.contains_key(&fallback)
+			{
+				return Err(VotingError::NotACandidate);
+			}
+			let preferred_votes = self.my_value_or_zero(preferred);
+			let leading = self
+				.candidate_list
+				.iter()
+				.all(|x| self.my_value_or_zero(*x) <= preferred_votes);
+			let chosen = if leading { preferred } else { fallback };
+			self.vote_candidate(owner, chosen, amount)?;
+			Ok(chosen)
+		}
+
+		// 按份额把手中的票全部投给多个候选人, weights为每个候选人的相对份额
+		// 按份额向下取整分配, 余下的票都给第一个候选人, 分到0票的候选人不投票
+		// 只支持线性模式, 所有分配都检查通过后才会写入
+		#[ink(message)]
+		pub fn vote_split(
+			&mut self,
+			owner: AccountId,
+			weights: Vec<(AccountId, u128)>,
+		) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			self.ensure_not_delegated(owner)?;
+			// 平方模式下花费的票数与投出的票数不同, 无法按比例花光
+			if self.mode != VotingMode::Linear {
+				return Err(VotingError::InvalidBallot);
+			}
+			let mut total_weight: u128 = 0;
+			for (i, (candidate, weight)) in weights.iter().enumerate() {
+				if !self.in_candidate_list.contains_key(candidate) {
+					return Err(VotingError::NotACandidate);
+				}
+				if weights[..i].iter().any(|(x, _)| x == candidate) {
+					return Err(VotingError::InvalidBallot);
+				}
+				total_weight = total_weight
+					.checked_add(*weight)
+					.ok_or(VotingError::Overflow)?;
+			}
+			if total_weight == 0 {
+				return Err(VotingError::InvalidBallot);
+			}
+			let balance = *self.voter_balance.get(&owner).unwrap_or(&0);
+			if balance == 0 {
+				return Err(VotingError::InsufficientTickets);
+			}
+			let mut allocations = weights
+				.iter()
+				.map(|(_, weight)| balance.checked_mul(*weight).map(|x| x / total_weight))
+				.collect::<Option<Vec<u128>>>()
+				.ok_or(VotingError::Overflow)?;
+			let allocated: u128 = allocations.iter().sum();
+			allocations[0] += balance - allocated;
+			let mut pending = Vec::new();
+			for ((candidate, _), amount) in weights.iter().zip(allocations.into_iter()) {
+				if amount > 0 {
+					pending.push((
+						*candidate,
+						amount,
+						self.prepare_vote(owner, *candidate, amount)?,
+					));
+				}
+			}
+			// 每个候选人单独检查时都是从原来的余额扣票, 写入时按实际剩余的票数更新
+			let mut remaining = balance;
+			for (candidate, amount, mut vote) in pending.into_iter() {
+				remaining -= amount;
+				vote.ticket_num = remaining;
+				self.apply_vote(owner, candidate, vote);
+				self.env().emit_event(VoteCast {
+					from: owner,
+					to: candidate,
+					amount,
+					voter_remaining: remaining,
+					candidate_total: self.my_value_or_zero(candidate),
+					block_number: self.env().block_number(),
+					election_id: self.election_id,
+				});
+			}
+			Ok(())
+		}
+
+		// 批量投票, 依次给每个候选人投票, 返回每一项的结果
+		// 某一项失败不影响其他项, 调用者不是owner时每一项都失败
+		#[ink(message)]
+		pub fn batch_vote(
+			&mut self,
+			owner: AccountId,
+			votes: Vec<(AccountId, u128)>,
+		) -> Vec<Result<()>> {
+			if self.env().caller() != owner {
+				return votes
+					.iter()
+					.map(|_| Err(VotingError::Unauthorized))
+					.collect();
+			}
+			let mut results = Vec::new();
+			let mut total_cast: u128 = 0;
+			for (candidate, amount) in votes.into_iter() {
+				let ret = self.vote_candidate_without_event(owner, candidate, amount);
+				if ret.is_ok() {
+					total_cast = total_cast.saturating_add(amount);
+				}
+				results.push(ret);
+			}
+			self.env().emit_event(BatchVoteEvent {
+				owner,
+				total_cast,
+				election_id: self.election_id,
+			});
+			results
+		}
+
+		// 排序投票, 按偏好从高到低提交候选人, 每人一张选票, 重复提交会覆盖之前的选票
+		// 排序投票不消耗手中的票, 只用于compute_irv_winner和compute_condorcet_winner
+		#[ink(message)]
+		pub fn ranked_choice_vote(
+			&mut self,
+			owner: AccountId,
+			preferences: Vec<AccountId>,
+		) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			self.ensure_not_delegated(owner)?;
+			if preferences.is_empty() {
+				return Err(VotingError::InvalidBallot);
+			}
+			for (i, candidate) in preferences.iter().enumerate() {
+				if !self.in_candidate_list.contains_key(candidate) {
+					return Err(VotingError::NotACandidate);
+				}
+				if preferences[..i].contains(candidate) {
+					return Err(VotingError::InvalidBallot);
+				}
+			}
+			self.ranked_ballots.insert(owner, preferences);
+			Ok(())
+		}
+
+		// 提交投票承诺, 只能在报名阶段由投票人自己提交, 重复提交会覆盖之前的承诺
+		// commitment 为blake2x256(candidate ++ nonce), 其中nonce按小端序编码
+		#[ink(message)]
+		pub fn commit_vote(&mut self, owner: AccountId, commitment: [u8; 32]) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_phase(VotingPhase::Registration)?;
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			self.commitments.insert(owner, commitment);
+			Ok(())
+		}
+
+		// 揭示投票, 只能在投票阶段进行, 与承诺相符时给候选人投一票
+		// 每个承诺只能揭示一次
+		#[ink(message)]
+		pub fn reveal_vote(
+			&mut self,
+			owner: AccountId,
+			candidate: AccountId,
+			nonce: u32,
+		) -> Result<()> {
+			self.ensure_phase(VotingPhase::Active)?;
+			let commitment = *self
+				.commitments
+				.get(&owner)
+				.ok_or(VotingError::NoCommitment)?;
+			if Self::commitment_of(candidate, nonce) != commitment {
+				return Err(VotingError::CommitmentMismatch);
+			}
+			self.vote_candidate(owner, candidate, 1)?;
+			self.commitments.take(&owner);
+			Ok(())
+		}
+
+		// it seems unit test failed when emit event if call vote_candidate function directly
+		fn vote_candidate_without_event(
+			&mut self,
+			owner: AccountId,
+			candidate: AccountId,
+			amout: u128,
+		) -> Result<()> {
+			let pending = self.prepare_vote(owner, candidate, amout)?;
+			self.apply_vote(owner, candidate, pending);
+			Ok(())
+		}
+
+		// 内部辅助函数检查一次投票并计算投票后的各项数值, 不修改状态
+		fn prepare_vote(
+			&self,
+			owner: AccountId,
+			candidate: AccountId,
+			amout: u128,
+		) -> Result<PendingVote> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			self.ensure_not_delegated(owner)?;
+			// 1. 首先确认被投票人在candidate_list中
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return Err(VotingError::NotACandidate);
+			}
+			// 2. 确认投票人有足够的票数
+			let prev = self.callee_vote_of(owner, candidate);
+			let vote_num = prev.checked_add(amout).ok_or(VotingError::Overflow)?;
+			// 平方模式按累计票数计算, 分多次投票和一次投出花费相同
+			let cost = match self.mode {
+				VotingMode::Linear => amout,
+				VotingMode::Quadratic => {
+					vote_num.checked_pow(2).ok_or(VotingError::Overflow)? - prev * prev
+				}
+			};
+			let ticket_num = self
+				.voter_balance
+				.get(&owner)
+				.unwrap_or(&0)
+				.checked_sub(cost)
+				.ok_or(VotingError::InsufficientTickets)?;
+			if self.next_receipt_id == u32::MAX {
+				return Err(VotingError::Overflow);
+			}
+			if vote_num > self.max_votes_per_voter_per_candidate {
+				return Err(VotingError::ExceedsVoterCap);
+			}
+			let token_weight = self.token_weight(owner)?;
+			// 候选人得到的票数要乘以投票人的权重和代币权重, 按时间加权时还要乘以时间权重
+			let credited = amout
+				.checked_mul(self.get_voter_weight(owner))
+				.and_then(|x| x.checked_mul(token_weight))
+				.ok_or(VotingError::Overflow)?;
+			let credited = self.time_weighted_votes(credited)?;
+			// 扣除手续费, 向下取整
+			let fee = credited
+				.checked_mul(u128::from(self.fee_bps))
+				.ok_or(VotingError::Overflow)?
+				/ 10_000;
+			let votes_received = (credited - fee)
+				.checked_add(self.my_value_or_zero(candidate))
+				.ok_or(VotingError::Overflow)?;
+			let raw_votes = self
+				.raw_votes_for(candidate)
+				.checked_add(amout)
+				.ok_or(VotingError::Overflow)?;
+			// 候选人得票数不能超过上限, 超过时整笔投票都不生效
+			if votes_received > self.max_votes_per_candidate {
+				return Err(VotingError::CandidateCapExceeded);
+			}
+			// 投票后候选人的得票占比不能超过上限, 超过时整笔投票都不生效
+			self.ensure_vote_share(candidate, votes_received)?;
+			Ok(PendingVote {
+				amount: amout,
+				fee,
+				ticket_num,
+				vote_num,
+				votes_received,
+				raw_votes,
+			})
+		}
+
+		// 内部辅助函数写入prepare_vote计算出的结果
+		fn apply_vote(&mut self, owner: AccountId, candidate: AccountId, pending: PendingVote) {
+			let amount = pending.amount;
+			self.apply_vote_tally(owner, candidate, pending);
+			self.record_vote(owner, candidate, amount);
+		}
+
+		// 内部辅助函数只更新票数, 闪电投票直接调用, 不留下投票时间, 区块记录和回执
+		fn apply_vote_tally(
+			&mut self,
+			owner: AccountId,
+			candidate: AccountId,
+			pending: PendingVote,
+		) {
+			// 3. 投票者票数减少, 优先花费质押换到的票
+			let spent = self
+				.voter_ticket_balance(owner)
+				.saturating_sub(pending.ticket_num);
+			self.spend_staked_tickets(owner, spent);
+			self.spent_tickets = self.spent_tickets.saturating_add(spent);
+			self.voter_balance.insert(owner, pending.ticket_num);
+			// 4. 更新voter
+			self.vote_num.insert((owner, candidate), pending.vote_num);
+			// 5. 候选人票数增加
+			let added = pending
+				.votes_received
+				.saturating_sub(self.my_value_or_zero(candidate));
+			self.total_votes_cast = self.total_votes_cast.saturating_add(added);
+			self.votes_received
+				.insert(candidate, pending.votes_received);
+			self.raw_votes_cast.insert(candidate, pending.raw_votes);
+			// 6. 手续费计入fee_treasury
+			if pending.fee > 0 {
+				let treasury = self.fee_treasury;
+				let total_fee = self.get_treasury_balance().saturating_add(pending.fee);
+				self.treasury_votes.insert(treasury, total_fee);
+			}
+		}
+
+		// 内部辅助函数记录一次投票的时间, 区块记录和回执
+		fn record_vote(&mut self, owner: AccountId, candidate: AccountId, amount: u128) {
+			self.vote_timestamps
+				.insert((owner, candidate), self.env().block_number());
+			// 7. 记录投票人投过的候选人
+			let voted = self.voter_candidates.entry(owner).or_insert_with(Vec::new);
+			if !voted.contains(&candidate) {
+				voted.push(candidate);
+			}
+			// 8. 记录本区块的投票
+			self.record_vote_history(owner, candidate, amount);
+			self.record_vote_velocity(candidate, amount);
+			// 9. 生成投票回执
+			let receipt_id = self.next_receipt_id;
+			self.next_receipt_id += 1;
+			self.receipt_details.insert(
+				receipt_id,
+				ReceiptDetails {
+					voter: owner,
+					candidate,
+					amount,
+					block: self.env().block_number(),
+				},
+			);
+			self.receipts
+				.entry(owner)
+				.or_insert_with(Vec::new)
+				.push(receipt_id);
+		}
+
+		// 获取投票回执
+		#[ink(message)]
+		pub fn get_receipt(&self, id: u32) -> Option<ReceiptDetails> {
+			self.receipt_details.get(&id).cloned()
+		}
+
+		// 获取投票人得到的全部回执id, 按生成顺序排列
+		#[ink(message)]
+		pub fn get_voter_receipts(&self, voter: AccountId) -> Vec<u32> {
+			self.receipts.get(&voter).cloned().unwrap_or_default()
+		}
+
+		// 内部辅助函数按区块记录投票, 并删除超出max_history_blocks的旧记录
+		fn record_vote_history(&mut self, owner: AccountId, candidate: AccountId, amount: u128) {
+			if self.max_history_blocks == 0 {
+				return;
+			}
+			let block = self.env().block_number();
+			let max_history_blocks = self.max_history_blocks;
+			let stale: Vec<_> = self
+				.vote_timestamps_by_block
+				.keys()
+				.copied()
+				.filter(|x| x.saturating_add(max_history_blocks) <= block)
+				.collect();
+			for x in stale {
+				self.vote_timestamps_by_block.take(&x);
+			}
+			self.vote_timestamps_by_block
+				.entry(block)
+				.or_insert_with(Vec::new)
+				.push((owner, candidate, amount));
+		}
+
+		// 内部辅助函数按(候选人, 区块号)累计票数, 并删除超出保留区块数的旧记录
+		fn record_vote_velocity(&mut self, candidate: AccountId, amount: u128) {
+			let block = self.env().block_number();
+			let retention = self.velocity_blocks();
+			let stale: Vec<_> = self
+				.vote_block_log
+				.keys()
+				.copied()
+				.filter(|(_, x)| x.saturating_add(retention) <= block)
+				.collect();
+			for key in stale {
+				self.vote_block_log.take(&key);
+			}
+			let bucket = self.vote_block_log.entry((candidate, block)).or_insert(0);
+			*bucket = bucket.saturating_add(amount);
+		}
+
+		// 内部辅助函数获取得票速度记录保留的区块数
+		fn velocity_blocks(&self) -> u32 {
+			if self.max_history_blocks > 0 {
+				self.max_history_blocks
+			} else {
+				DEFAULT_VELOCITY_BLOCKS
+			}
+		}
+
+		// 获取候选人在[当前区块 - window_blocks, 当前区块]内得到的票数(未加权), 用于显示得票速度
+		// 窗口超过保留的区块数时按保留的区块数计算
+		#[ink(message)]
+		pub fn get_candidate_velocity(&self, candidate: AccountId, window_blocks: u32) -> u128 {
+			let now = self.env().block_number();
+			let window = window_blocks.min(self.velocity_blocks() - 1);
+			(now.saturating_sub(window)..=now)
+				.filter_map(|block| self.vote_block_log.get(&(candidate, block)))
+				.fold(0u128, |sum, amount| sum.saturating_add(*amount))
+		}
+
+		// 获取since_block之后(不含)各区块的投票记录(区块号, 投票人, 候选人, 票数), 按区块号排列
+		// 只保留最近max_history_blocks个区块的记录
+		#[ink(message)]
+		pub fn get_votes_since_block(
+			&self,
+			since_block: BlockNumber,
+		) -> Vec<(BlockNumber, AccountId, AccountId, u128)> {
+			let mut blocks: Vec<_> = self
+				.vote_timestamps_by_block
+				.keys()
+				.copied()
+				.filter(|x| *x > since_block)
+				.collect();
+			blocks.sort_unstable();
+			blocks
+				.into_iter()
+				.flat_map(|block| {
+					self.vote_timestamps_by_block
+						.get(&block)
+						.into_iter()
+						.flatten()
+						.map(move |(voter, candidate, amount)| (block, *voter, *candidate, *amount))
+				})
+				.collect()
+		}
+
+		// 弃权, 消耗amount张票但不计入任何候选人
+		#[ink(message)]
+		pub fn abstain_vote(&mut self, owner: AccountId, amount: u128) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			self.ensure_not_delegated(owner)?;
+			if amount == 0 {
+				return Err(VotingError::ZeroAmount);
+			}
+			let ticket_num = self
+				.voter_ticket_balance(owner)
+				.checked_sub(amount)
+				.ok_or(VotingError::InsufficientTickets)?;
+			let abstentions = self
+				.voter_abstentions(owner)
+				.checked_add(amount)
+				.ok_or(VotingError::Overflow)?;
+			let total_abstentions = self
+				.total_abstentions
+				.checked_add(amount)
+				.ok_or(VotingError::Overflow)?;
+
+			self.spend_staked_tickets(owner, amount);
+			self.voter_balance.insert(owner, ticket_num);
+			self.abstentions.insert(owner, abstentions);
+			self.total_abstentions = total_abstentions;
+			Ok(())
+		}
+
+		// 弃权票总数
+		#[ink(message)]
+		pub fn total_abstentions(&self) -> u128 {
+			self.total_abstentions
+		}
+
+		// 某投票人投出的弃权票数
+		#[ink(message)]
+		pub fn voter_abstentions(&self, voter: AccountId) -> u128 {
+			*self.abstentions.get(&voter).unwrap_or(&0)
+		}
+
+		// 撤回投票, 只能由owner本人调用
+		// 撤回amount票后退还amount张票给投票人
+		#[ink(message)]
+		pub fn revoke_vote(
+			&mut self,
+			owner: AccountId,
+			candidate: AccountId,
+			amount: u128,
+		) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			self.ensure_revocable()?;
+			// 候选人被移除后已投的票视为已花费, 不能撤回
+			if !self.in_candidate_list.contains_key(&candidate) {
+				return Err(VotingError::NotACandidate);
+			}
+			let vote_num = self
+				.callee_vote_of(owner, candidate)
+				.checked_sub(amount)
+				.ok_or(VotingError::InsufficientVotes)?;
+			let weighted = amount
+				.checked_mul(self.get_voter_weight(owner))
+				.ok_or(VotingError::Overflow)?;
+			let votes_received = self
+				.my_value_or_zero(candidate)
+				.checked_sub(weighted)
+				.ok_or(VotingError::InsufficientVotes)?;
+			let raw_votes = self
+				.raw_votes_for(candidate)
+				.checked_sub(amount)
+				.ok_or(VotingError::InsufficientVotes)?;
+			let ticket_num = self
+				.voter_ticket_balance(owner)
+				.checked_add(amount)
+				.ok_or(VotingError::Overflow)?;
+
+			if vote_num == 0 {
+				self.vote_num.take(&(owner, candidate));
+				self.vote_timestamps.take(&(owner, candidate));
+			} else {
+				self.vote_num.insert((owner, candidate), vote_num);
+			}
+			self.total_votes_cast = self.total_votes_cast.saturating_sub(weighted);
+			self.votes_received.insert(candidate, votes_received);
+			self.raw_votes_cast.insert(candidate, raw_votes);
+			self.voter_balance.insert(owner, ticket_num);
+			self.relock_staked_tickets(owner, amount);
+			self.spent_tickets = self.spent_tickets.saturating_sub(amount);
+			self.env().emit_event(VoteRevoked {
+				owner,
+				candidate,
+				amount,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 闪电投票, 只能由owner本人调用
+		// 先投出amount票, 再调用flash_hook_contract的回调, 最后撤回这些票
+		// 票数和投票人手中的票都会恢复, 不生成投票回执, 也不更新投票时间和区块投票记录
+		// 回调失败时同样会撤回投票, 然后返回FlashHookFailed
+		#[ink(message)]
+		pub fn flash_vote(
+			&mut self,
+			owner: AccountId,
+			candidate: AccountId,
+			amount: u128,
+		) -> Result<()> {
+			let hook = self
+				.flash_hook_contract
+				.ok_or(VotingError::FlashHookNotSet)?;
+			if self.env().caller() != owner {
+				return Err(VotingError::Unauthorized);
+			}
+			self.ensure_revocable()?;
+			let pending = self.prepare_vote(owner, candidate, amount)?;
+			self.apply_vote_tally(owner, candidate, pending);
+			let hooked = self.invoke_flash_hook(hook, owner, candidate, amount);
+			// 刚投出的票一定可以撤回, 撤回失败时回滚整个交易
+			self.revoke_vote(owner, candidate, amount)
+				.expect("flash vote must be revocable");
+			hooked
+		}
+
+		// 让投票人投给某候选人的票过期, 任何人都可调用
+		// 最后一次投票的区块号加上vote_expiry_blocks小于当前区块号时才会过期
+		// 过期的票从候选人票数中扣除, 但不会退回给投票人
+		#[ink(message)]
+		pub fn expire_votes(&mut self, voter: AccountId, candidate: AccountId) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			self.ensure_revocable()?;
+			let voted_at = *self
+				.vote_timestamps
+				.get(&(voter, candidate))
+				.ok_or(VotingError::InsufficientVotes)?;
+			if self.vote_expiry_blocks == 0
+				|| voted_at.saturating_add(self.vote_expiry_blocks) >= self.env().block_number()
+			{
+				return Err(VotingError::VoteNotExpired);
+			}
+			let amount = self.callee_vote_of(voter, candidate);
+			let weighted = amount
+				.checked_mul(self.get_voter_weight(voter))
+				.ok_or(VotingError::Overflow)?;
+			let votes_received = self
+				.my_value_or_zero(candidate)
+				.checked_sub(weighted)
+				.ok_or(VotingError::InsufficientVotes)?;
+			let raw_votes = self
+				.raw_votes_for(candidate)
+				.checked_sub(amount)
+				.ok_or(VotingError::InsufficientVotes)?;
+
+			self.vote_num.take(&(voter, candidate));
+			self.vote_timestamps.take(&(voter, candidate));
+			self.total_votes_cast = self.total_votes_cast.saturating_sub(weighted);
+			self.votes_received.insert(candidate, votes_received);
+			self.raw_votes_cast.insert(candidate, raw_votes);
+			self.env().emit_event(VoteExpired {
+				owner: voter,
+				candidate,
+				amount,
+				election_id: self.election_id,
+			});
+			Ok(())
+		}
+
+		// 记录当前各候选人票数的快照, 返回快照id, 仅管理员可调用
+		#[ink(message)]
+		pub fn take_snapshot(&mut self) -> Result<u32> {
+			self.ensure_admin()?;
+			let id = self.snapshots.len();
+			if id >= self.max_snapshots {
+				return Err(VotingError::SnapshotLimitReached);
+			}
+			let votes = self.get_current_votes();
+			self.snapshots.insert(id, votes);
+			self.snapshot_blocks.insert(id, self.env().block_number());
+			Ok(id)
+		}
+
+		// 获取某个快照中各候选人的票数
+		#[ink(message)]
+		pub fn get_snapshot(&self, id: u32) -> Option<Vec<VoteOfCandidate>> {
+			self.snapshots.get(&id).cloned()
+		}
+
+		// 获取某个快照记录时的区块高度
+		#[ink(message)]
+		pub fn get_snapshot_block(&self, id: u32) -> Option<BlockNumber> {
+			self.snapshot_blocks.get(&id).copied()
+		}
+
+		// 获取某用户被投票的数量
+		#[ink(message)]
+		pub fn total_votes_for(&self, candidate: AccountId) -> u128 {
+			self.my_value_or_zero(candidate)
+		}
+
+		// 否决某候选人, 只有买过票的用户可以在投票阶段调用, 每人对每个候选人只能否决一次
+		#[ink(message)]
+		pub fn veto_candidate(&mut self, target: AccountId) -> Result<()> {
+			self.ensure_not_paused()?;
+			self.ensure_not_ended()?;
+			self.ensure_phase(VotingPhase::Active)?;
+			let caller = self.env().caller();
+			if !self.has_purchased.contains_key(&caller) {
+				return Err(VotingError::Unauthorized);
+			}
+			if !self.in_candidate_list.contains_key(&target) {
+				return Err(VotingError::NotACandidate);
+			}
+			if self.veto_voters.contains_key(&(caller, target)) {
+				return Err(VotingError::AlreadyVetoed);
+			}
+			let vetoes = self
+				.vetoes_for(target)
+				.checked_add(1)
+				.ok_or(VotingError::Overflow)?;
+			self.vetoes.insert(target, vetoes);
+			self.veto_voters.insert((caller, target), ());
+			Ok(())
+		}
+
+		// 获取某候选人被否决的次数
+		#[ink(message)]
+		pub fn vetoes_for(&self, candidate: AccountId) -> u32 {
+			*self.vetoes.get(&candidate).unwrap_or(&0)
+		}
+
+		// 某候选人是否被否决, 即否决次数超过已投票数的veto_threshold_bps
+		#[ink(message)]
+		pub fn is_vetoed(&self, candidate: AccountId) -> bool {
+			if self.veto_threshold_bps == 0 {
+				return false;
+			}
+			u128::from(self.vetoes_for(candidate)).saturating_mul(10_000)
+				> self
+					.total_candidate_votes()
+					.saturating_mul(u128::from(self.veto_threshold_bps))
+		}
+
+		// 候选人得到的总票数是否达到了quorum_votes
+		#[ink(message)]
+		pub fn is_quorum_met(&self) -> bool {
+			self.total_candidate_votes() >= self.quorum_votes
+		}
+
+		// 获取当前得票最多的候选人, 平票时返回在candidate_list中靠前的候选人
+		// 票数低于min_votes_threshold或被否决的候选人不能获胜
+		// 只能在投票结束后查询, 没有人得票或总票数不足quorum_votes时返回None
+		#[ink(message)]
+		pub fn get_winner(&self) -> Result<Option<AccountId>> {
+			self.ensure_phase(VotingPhase::Ended)?;
+			if !self.is_quorum_met() {
+				return Ok(None);
+			}
+			let mut winner = None;
+			let mut max_votes = 0;
+			for x in self.candidate_list.iter() {
+				if self.is_vetoed(*x) {
+					continue;
+				}
+				let votes = self.my_value_or_zero(*x);
+				if votes > max_votes && votes >= self.min_votes_threshold {
+					max_votes = votes;
+					winner = Some(*x);
+				}
+			}
+			Ok(winner)
+		}
+
+		// 获取得票最多的seats个获胜者, 按票数从高到低排序, 平票时按加入顺序排列
+		// 与最后一个席位平票的候选人也算作获胜者, 此时返回的人数会多于seats
+		// 票数低于min_votes_threshold或被否决的候选人不能获胜
+		// 只能在投票结束后查询, 总票数不足quorum_votes时没有获胜者
+		#[ink(message)]
+		pub fn get_winners(&self) -> Result<Vec<AccountId>> {
+			self.ensure_phase(VotingPhase::Ended)?;
+			let seats = self.seats as usize;
+			if seats == 0 || !self.is_quorum_met() {
+				return Ok(Vec::new());
+			}
+			let mut ranked: Vec<(AccountId, u128)> = self
+				.candidate_list
+				.iter()
+				.filter(|x| !self.is_vetoed(**x))
+				.map(|x| (*x, self.my_value_or_zero(*x)))
+				.filter(|x| x.1 >= self.min_votes_threshold)
+				.collect();
+			// sort_by是稳定排序, 平票时保持加入顺序
+			ranked.sort_by(|a, b| b.1.cmp(&a.1));
+			let cutoff = ranked.get(seats - 1).map(|x| x.1);
+			Ok(ranked
+				.into_iter()
+				.enumerate()
+				.take_while(|(i, x)| *i < seats || Some(x.1) == cutoff)
+				.map(|(_, x)| x.0)
+				.collect())
+		}
+
+		// 按即时决选(IRV)规则计算排序投票的获胜者
+		// 每轮统计每张选票中排名最高且未被淘汰的候选人, 得票过半者获胜
+		// 否则淘汰得票最少的候选人(平票时淘汰最后加入的)后进入下一轮
+		// 只能在投票结束后查询, 没有有效选票时返回None
+		#[ink(message)]
+		pub fn compute_irv_winner(&self) -> Result<Option<AccountId>> {
+			self.ensure_phase(VotingPhase::Ended)?;
+			let mut remaining: Vec<AccountId> = self.candidate_list.iter().copied().collect();
+			while !remaining.is_empty() {
+				let mut tally: Vec<u32> = ink_prelude::vec![0; remaining.len()];
+				let mut total: u32 = 0;
+				for ballot in self.ranked_ballots.values() {
+					if let Some(i) = ballot
+						.iter()
+						.find_map(|x| remaining.iter().position(|y| y == x))
+					{
+						tally[i] += 1;
+						total += 1;
+					}
+				}
+				if total == 0 {
+					return Ok(None);
+				}
+				if let Some(i) = tally.iter().position(|n| *n > total / 2) {
+					return Ok(Some(remaining[i]));
+				}
+				let min = tally.iter().copied().min().unwrap_or(0);
+				if let Some(i) = tally.iter().rposition(|n| *n == min) {
+					remaining.remove(i);
+				}
+			}
+			Ok(None)
+		}
+
+		// 计算孔多塞获胜者, 即两两比较中胜过其他所有候选人的候选人, 不存在时返回None
+		// 只统计排序投票, 排名靠前的候选人胜过排名靠后和没有排名的候选人, 每张选票算一票
+		#[ink(message)]
+		pub fn compute_condorcet_winner(&self) -> Option<AccountId> {
+			self.candidate_list.iter().copied().find(|x| {
+				self.candidate_list
+					.iter()
+					.filter(|y| *y != x)
+					.all(|y| self.get_pairwise_votes(*x, *y) > self.get_pairwise_votes(*y, *x))
+			})
+		}
+
+		// 获取排序投票中偏好winner胜过loser的选票数, 按当前的候选人列表和选票计算
+		#[ink(message)]
+		pub fn get_pairwise_votes(&self, winner: AccountId, loser: AccountId) -> u128 {
+			if winner == loser
+				|| !self.in_candidate_list.contains_key(&winner)
+				|| !self.in_candidate_list.contains_key(&loser)
+			{
+				return 0;
+			}
+			self.ranked_ballots
+				.values()
+				.filter(|ballot| Self::ballot_prefers(ballot, winner, loser))
+				.count() as u128
+		}
+
+		// 内部辅助函数判断选票是否偏好winner胜过loser
+		// 排名靠前的候选人胜过排名靠后和没有排名的候选人
+		fn ballot_prefers(ballot: &[AccountId], winner: AccountId, loser: AccountId) -> bool {
+			match ballot.iter().position(|x| *x == winner) {
+				Some(i) => !ballot[..i].contains(&loser),
+				None => false,
+			}
+		}
+
+		// 获取某用户被投票的数量
+		#[ink(message)]
+		pub fn callee_vote_of(&self, callee: AccountId, candidate: AccountId) -> u128 {
+			*self.vote_num.get(&(callee, candidate)).unwrap_or(&0)
+		}
+
+		// 获取某候选人获得的未加权票数
+		#[ink(message)]
+		pub fn raw_votes_for(&self, candidate: AccountId) -> u128 {
+			*self.raw_votes_cast.get(&candidate).unwrap_or(&0)
+		}
+
+		// 获取某投票人投给每个候选人的票数, 已全部撤回的候选人不计入
+		#[ink(message)]
+		pub fn get_voter_history(&self, voter: AccountId) -> Vec<(AccountId, u128)> {
+			self.voter_candidates
+				.get(&voter)
+				.map(|candidates| {
+					candidates
+						.iter()
+						.map(|x| (*x, self.callee_vote_of(voter, *x)))
+						.filter(|(_, count)| *count > 0)
+						.collect()
+				})
+				.unwrap_or_default()
+		}
+
+		// 获取某投票人已经投出的总票数, 撤回的票不计入
+		// 与voter_ticket_balance相对, 后者是手中剩余的票数
+		// 线性模式下等于已花费的票数, 平方模式下花费的票数更多
+		#[ink(message)]
+		pub fn get_votes_cast_by_voter(&self, voter: AccountId) -> u128 {
+			self.voter_candidates
+				.get(&voter)
+				.map(|candidates| {
+					candidates
+						.iter()
+						.map(|x| self.callee_vote_of(voter, *x))
+						.sum()
+				})
+				.unwrap_or(0)
+		}
+
+		// 获取某投票人的概况
+		#[ink(message)]
+		pub fn get_voter_profile(&self, voter: AccountId) -> VoterProfile {
+			VoterProfile {
+				ticket_balance: *self.voter_balance.get(&voter).unwrap_or(&0),
+				total_spent: self.get_votes_cast_by_voter(voter),
+				delegated_to: self.delegate_of(voter),
+				votes: self.get_voter_history(voter),
+			}
+		}
+
+		// 内部辅助函数确认调用者是否为管理员
+		// 紧急停止后管理员也不能再修改合约
+		fn ensure_admin(&self) -> Result<()> {
+			self.ensure_not_stopped()?;
+			if !self.is_admin(self.env().caller()) {
+				return Err(VotingError::Unauthorized);
+			}
+			// 需要多个管理员批准时不能直接调用, 只能通过execute_action执行
+			if self.required_approvals > 1 && !self.executing_action {
+				return Err(VotingError::Unauthorized);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数计算经过blocks个区块后剩余的权重, 单位为万分之一
+		// (1 - decay_rate)^blocks, 用快速幂计算, 每一步都向下取整
+		fn decay_factor_bps(&self, blocks: BlockNumber) -> u128 {
+			let mut base = 10_000u128.saturating_sub(u128::from(self.decay_rate_bps_per_block));
+			let mut factor = 10_000u128;
+			let mut n = blocks;
+			while n > 0 && factor > 0 {
+				if n & 1 == 1 {
+					factor = factor * base / 10_000;
+				}
+				base = base * base / 10_000;
+				n >>= 1;
+			}
+			factor
+		}
+
+		// 内部辅助函数获取第一名和第二名的票数
+		fn top_two_votes(&self) -> (u128, u128) {
+			let mut first = 0;
+			let mut second = 0;
+			for x in self.candidate_list.iter() {
+				let votes = self.my_value_or_zero(*x);
+				if votes > first {
+					second = first;
+					first = votes;
+				} else if votes > second {
+					second = votes;
+				}
+			}
+			(first, second)
+		}
+
+		// 内部辅助函数把候选人从候选人列表中移除, 并清空其得票
+		fn take_candidate(&mut self, candidate: AccountId) {
+			self.in_candidate_list.take(&candidate);
+			// 保持剩余候选人的顺序
+			let remaining: Vec<_> = self
+				.candidate_list
+				.iter()
+				.copied()
+				.filter(|x| *x != candidate)
+				.collect();
+			self.candidate_list.clear();
+			for x in remaining.into_iter() {
+				self.candidate_list.push(x);
+			}
+			let removed = self.my_value_or_zero(candidate);
+			self.total_votes_cast = self.total_votes_cast.saturating_sub(removed);
+			self.votes_received.take(&candidate);
+			self.raw_votes_cast.take(&candidate);
+			// 清除投给该候选人的投票记录, 之后再次添加该候选人时从零开始
+			let pairs: Vec<_> = self
+				.vote_num
+				.keys()
+				.copied()
+				.filter(|(_, x)| *x == candidate)
+				.collect();
+			for pair in pairs {
+				self.vote_num.take(&pair);
+				self.vote_timestamps.take(&pair);
+				self.conviction_bonus.take(&pair);
+			}
+			let pairs: Vec<_> = self
+				.insurance
+				.keys()
+				.copied()
+				.filter(|(_, x)| *x == candidate)
+				.collect();
+			for pair in pairs {
+				self.insurance.take(&pair);
+			}
+			let pairs: Vec<_> = self
+				.vote_block_log
+				.keys()
+				.copied()
+				.filter(|(x, _)| *x == candidate)
+				.collect();
+			for pair in pairs {
+				self.vote_block_log.take(&pair);
+			}
+		}
+
+		// 内部辅助函数用新的列表替换管理员列表
+		fn replace_admins(&mut self, admins: Vec<AccountId>) {
+			self.admins.clear();
+			for x in admins.into_iter() {
+				self.admins.push(x);
+			}
+		}
+
+		// 内部辅助函数判断是否是多签操作支持的函数选择器
+		fn is_known_action(selector: [u8; 4]) -> bool {
+			[
+				ACTION_ADD_CANDIDATE,
+				ACTION_REMOVE_CANDIDATE,
+				ACTION_ADVANCE_PHASE,
+				ACTION_PAUSE,
+				ACTION_UNPAUSE,
+				ACTION_SET_ADMINS,
+				ACTION_BATCH_ADD_CANDIDATES,
+				ACTION_LOCK_CANDIDATE_LIST,
+				ACTION_TRANSFER_ADMIN,
+				ACTION_OVERRIDE_VOTE_COUNT,
+				ACTION_RESET_ELECTION,
+				ACTION_IMPORT_PREVIOUS_VOTES,
+				ACTION_SET_VOTER_WEIGHT,
+				ACTION_EMERGENCY_STOP,
+				ACTION_SET_PROXY_CONTRACT,
+				ACTION_SET_METADATA,
+				ACTION_SET_MERKLE_ROOT,
+				ACTION_SET_WHITELIST_ENABLED,
+				ACTION_MINT_TOKENS,
+				ACTION_SET_TOKEN_PRICE,
+				ACTION_IMPORT_STATE,
+				ACTION_REGISTER_VOTER,
+				ACTION_TAKE_SNAPSHOT,
+			]
+			.contains(&selector)
+		}
+
+		// 内部辅助函数解码参数并调用选择器对应的消息
+		fn dispatch_action(&mut self, selector: [u8; 4], args: &[u8]) -> Result<()> {
+			let mut input = args;
+			match selector {
+				ACTION_ADD_CANDIDATE => {
+					let candidate = Self::decode_action_args(&mut input)?;
+					self.add_candidate(candidate)
+				}
+				ACTION_REMOVE_CANDIDATE => {
+					let candidate = Self::decode_action_args(&mut input)?;
+					self.remove_candidate(candidate)
+				}
+				ACTION_ADVANCE_PHASE => self.advance_phase(),
+				ACTION_PAUSE => self.pause(),
+				ACTION_UNPAUSE => self.unpause(),
+				ACTION_SET_ADMINS => {
+					let (admins, required_approvals) = Self::decode_action_args(&mut input)?;
+					self.set_admins(admins, required_approvals)
+				}
+				ACTION_BATCH_ADD_CANDIDATES => {
+					let candidates = Self::decode_action_args(&mut input)?;
+					self.batch_add_candidates(candidates).map(|_| ())
+				}
+				ACTION_LOCK_CANDIDATE_LIST => self.lock_candidate_list(),
+				ACTION_TRANSFER_ADMIN => {
+					let (old_admin, new_admin) = Self::decode_action_args(&mut input)?;
+					self.ensure_admin()?;
+					if !self.is_admin(old_admin) {
+						return Err(VotingError::InvalidActionArgs);
+					}
+					self.initiate_admin_transfer(old_admin, new_admin);
+					Ok(())
+				}
+				ACTION_OVERRIDE_VOTE_COUNT => {
+					let (candidate, new_count) = Self::decode_action_args(&mut input)?;
+					self.override_vote_count(candidate, new_count)
+				}
+				ACTION_RESET_ELECTION => self.reset_election(),
+				ACTION_IMPORT_PREVIOUS_VOTES => {
+					let legacy = Self::decode_action_args(&mut input)?;
+					self.import_previous_votes(legacy)
+				}
+				ACTION_SET_VOTER_WEIGHT => {
+					let (voter, weight) = Self::decode_action_args(&mut input)?;
+					self.set_voter_weight(voter, weight)
+				}
+				ACTION_EMERGENCY_STOP => self.emergency_stop(),
+				ACTION_SET_PROXY_CONTRACT => {
+					let proxy = Self::decode_action_args(&mut input)?;
+					self.set_proxy_contract(proxy)
+				}
+				ACTION_SET_METADATA => {
+					let new_metadata = Self::decode_action_args(&mut input)?;
+					self.set_metadata(new_metadata)
+				}
+				ACTION_SET_MERKLE_ROOT => {
+					let root = Self::decode_action_args(&mut input)?;
+					self.set_merkle_root(root)
+				}
+				ACTION_SET_WHITELIST_ENABLED => {
+					let enabled = Self::decode_action_args(&mut input)?;
+					self.set_whitelist_enabled(enabled)
+				}
+				ACTION_MINT_TOKENS => {
+					let amount = Self::decode_action_args(&mut input)?;
+					self.mint_tokens(amount)
+				}
+				ACTION_SET_TOKEN_PRICE => {
+					let new_price = Self::decode_action_args(&mut input)?;
+					self.set_token_price(new_price)
+				}
+				ACTION_IMPORT_STATE => {
+					let snapshot = Self::decode_action_args(&mut input)?;
+					self.import_state(snapshot)
+				}
+				ACTION_REGISTER_VOTER => {
+					let voter = Self::decode_action_args(&mut input)?;
+					self.register_voter(voter)
+				}
+				ACTION_TAKE_SNAPSHOT => self.take_snapshot().map(|_| ()),
+				_ => Err(VotingError::UnknownAction),
+			}
+		}
+
+		// 内部辅助函数解码多签操作的参数
+		fn decode_action_args<T: scale::Decode>(input: &mut &[u8]) -> Result<T> {
+			T::decode(input).map_err(|_| VotingError::InvalidActionArgs)
+		}
+
+		// 内部辅助函数确认投票人没有委托投票权
+		fn ensure_not_delegated(&self, voter: AccountId) -> Result<()> {
+			if self.delegations.contains_key(&voter) {
+				return Err(VotingError::AlreadyDelegated);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数确认当前处于指定的投票阶段
+		fn ensure_phase(&self, phase: VotingPhase) -> Result<()> {
+			if self.phase != phase {
+				return Err(VotingError::WrongPhase);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数确认合约未被暂停, 紧急停止视为永久暂停
+		fn ensure_not_paused(&self) -> Result<()> {
+			self.ensure_not_stopped()?;
+			if self.paused {
+				return Err(VotingError::ContractPaused);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数确认候选人列表没有被锁定
+		fn ensure_candidate_list_unlocked(&self) -> Result<()> {
+			if self.candidate_list_locked {
+				return Err(VotingError::CandidateListLocked);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数确认合约未被紧急停止
+		fn ensure_not_stopped(&self) -> Result<()> {
+			if self.stopped {
+				return Err(VotingError::ContractStopped);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数确认投票尚未截止
+		fn ensure_not_ended(&self) -> Result<()> {
+			if self.has_ended() {
+				return Err(VotingError::VotingEnded);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数计算时间加权后的票数
+		// 权重为 剩余时间 / 总时长 + 1, 部署时为2倍, 随时间线性降到截止时的1倍
+		fn time_weighted_votes(&self, votes: u128) -> Result<u128> {
+			let duration = self.deadline.saturating_sub(self.vote_start_time);
+			if !self.time_weighted || duration == 0 {
+				return Ok(votes);
+			}
+			let elapsed = self
+				.env()
+				.block_timestamp()
+				.saturating_sub(self.vote_start_time);
+			let remaining = duration.saturating_sub(elapsed);
+			// 先乘后除, 避免权重被截断为整数
+			votes
+				.checked_mul(u128::from(duration) + u128::from(remaining))
+				.map(|x| x / u128::from(duration))
+				.ok_or(VotingError::Overflow)
+		}
+
+		// 内部辅助函数验证默克尔证明, 从叶子逐层向上计算出根并与merkle_root比较
+		fn verify_merkle_proof(&self, voter: AccountId, proof: &[[u8; 32]]) -> bool {
+			let mut node = Self::merkle_leaf(voter);
+			for sibling in proof.iter() {
+				node = Self::merkle_parent(&node, sibling);
+			}
+			node == self.merkle_root
+		}
+
+		// 内部辅助函数计算默克尔树的叶子
+		fn merkle_leaf(voter: AccountId) -> [u8; 32] {
+			let mut output = [0u8; 32];
+			ink_env::hash_encoded::<ink_env::hash::Sha2x256, _>(&voter, &mut output);
+			output
+		}
+
+		// 内部辅助函数计算默克尔树的父节点
+		// 两个子节点按字节序排列后再拼接, 这样证明中不需要记录左右位置
+		fn merkle_parent(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+			let (left, right) = if a <= b { (a, b) } else { (b, a) };
+			let mut input = [0u8; 64];
+			input[..32].copy_from_slice(left);
+			input[32..].copy_from_slice(right);
+			let mut output = [0u8; 32];
+			ink_env::hash_bytes::<ink_env::hash::Sha2x256>(&input, &mut output);
+			output
+		}
+
+		// 内部辅助函数计算投票承诺
+		fn commitment_of(candidate: AccountId, nonce: u32) -> [u8; 32] {
+			let mut output = [0u8; 32];
+			ink_env::hash_encoded::<ink_env::hash::Blake2x256, _>(&(candidate, nonce), &mut output);
+			output
+		}
+
+		// 内部辅助函数按当前状态还原部署参数, 增发和调价后的值也会被带上
+		fn current_config(&self) -> VotingConfig {
+			VotingConfig {
+				total_tokens: self.total_tokens,
+				max_supply: self.max_supply,
+				token_price: self.token_price,
+				min_price: self.min_price,
+				duration_ms: self.deadline.saturating_sub(self.vote_start_time),
+				mode: self.mode,
+				enable_whitelist: self.whitelist_enabled,
+				merkle_root: self.merkle_root,
+				max_snapshots: self.max_snapshots,
+				max_votes_per_candidate: self.max_votes_per_candidate,
+				seats: self.seats,
+				max_votes_per_voter_per_candidate: self.max_votes_per_voter_per_candidate,
+				min_votes_threshold: self.min_votes_threshold,
+				max_tickets_per_voter: self.max_tickets_per_voter,
+				time_weighted: self.time_weighted,
+				metadata: self.metadata.clone(),
+				token_contract: self.token_contract,
+				vote_expiry_blocks: self.vote_expiry_blocks,
+				veto_threshold_bps: self.veto_threshold_bps,
+				quorum_votes: self.quorum_votes,
+				require_payment: self.require_payment,
+				max_history_blocks: self.max_history_blocks,
+				auction_step: self.auction_step,
+				election_id: self.election_id,
+				conviction_period: self.conviction_period,
+				max_conviction_multiplier: self.max_conviction_multiplier,
+				warning_window_ms: self.warning_window_ms,
+				decay_rate_bps_per_block: self.decay_rate_bps_per_block,
+				stake_token: self.stake_token,
+				fee_bps: self.fee_bps,
+				fee_treasury: self.fee_treasury,
+				flash_hook_contract: self.flash_hook_contract,
+				max_votes_share_bps: self.max_votes_share_bps,
+				min_votes_for_share_cap: self.min_votes_for_share_cap,
+				token_weight_divisor: self.token_weight_divisor,
+				max_token_weight: self.max_token_weight,
+			}
+		}
+
+		// 内部辅助函数计算log2(x)的定点数, 小数部分有LOG2_FRACTION_BITS位, x不能为0
+		// 先取整数部分, 再把x规格化到[1, 2)后反复平方逐位求出小数部分
+		fn log2_fixed(x: u128) -> u128 {
+			let int = 127 - x.leading_zeros();
+			// 用63位小数表示规格化后的x
+			let mut m = if int >= 63 {
+				x >> (int - 63)
+			} else {
+				x << (63 - int)
+			};
+			let mut result = u128::from(int) << LOG2_FRACTION_BITS;
+			for i in (0..LOG2_FRACTION_BITS).rev() {
+				m = (m * m) >> 63;
+				if m >= 1 << 64 {
+					m >>= 1;
+					result |= 1 << i;
+				}
+			}
+			result
+		}
+
+		// 内部辅助函数计算所有候选人得到的总票数
+		fn total_candidate_votes(&self) -> u128 {
+			self.candidate_list.iter().fold(0u128, |sum, x| {
+				sum.saturating_add(self.my_value_or_zero(*x))
+			})
+		}
+
+		// 内部辅助函数确认已投的票可以被扣除
+		// 时间权重和代币余额都会变化, 无法确定应扣除的票数
+		// 平方模式下花费的票数不等于投出的票数, 按票数退还会多退或少退
+		fn ensure_revocable(&self) -> Result<()> {
+			if self.mode == VotingMode::Quadratic
+				|| self.time_weighted
+				|| self.token_contract.is_some()
+				|| self.conviction_period > 0
+				|| self.fee_bps > 0
+			{
+				return Err(VotingError::RevokeNotSupported);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数计算退回tickets张票时按平均票价应退的金额, 以及扣除后剩余的支付记录
+		// 最多只退回用原生代币买到的票, 转入和质押换到的票不退款
+		fn paid_refund(&self, owner: AccountId, tickets: u128) -> (Balance, (u128, Balance)) {
+			let (bought, paid) = *self.ticket_payments.get(&owner).unwrap_or(&(0, 0));
+			let tickets = tickets.min(bought);
+			if tickets == 0 {
+				return (0, (bought, paid));
+			}
+			let refund = match paid.checked_mul(tickets) {
+				Some(x) => x / bought,
+				None => paid / bought * tickets,
+			};
+			(refund, (bought - tickets, paid - refund))
+		}
+
+		// 内部辅助函数更新支付记录, 已经全部退回时删除
+		fn update_ticket_payment(&mut self, owner: AccountId, payment: (u128, Balance)) {
+			if payment.0 == 0 {
+				self.ticket_payments.take(&owner);
+			} else {
+				self.ticket_payments.insert(owner, payment);
+			}
+		}
+
+		// 内部辅助函数记录投票人花费的票, 先从质押换到的票中扣除
+		fn spend_staked_tickets(&mut self, owner: AccountId, spent: u128) {
+			let locked = self.staked_tickets_of(owner);
+			let moved = locked.min(spent);
+			if moved == 0 {
+				return;
+			}
+			self.staked_tickets.insert(owner, locked - moved);
+			let cast = self.staked_tickets_cast.entry(owner).or_insert(0);
+			*cast = cast.saturating_add(moved);
+		}
+
+		// 内部辅助函数确认candidate的得票变为votes后得票占比不超过max_votes_share_bps
+		fn ensure_vote_share(&self, candidate: AccountId, votes: u128) -> Result<()> {
+			if self.max_votes_share_bps == 0 {
+				return Ok(());
+			}
+			let total_votes_cast = self
+				.total_votes_cast
+				.saturating_sub(self.my_value_or_zero(candidate))
+				.saturating_add(votes);
+			if total_votes_cast >= self.min_votes_for_share_cap
+				&& votes.saturating_mul(10_000)
+					> total_votes_cast.saturating_mul(u128::from(self.max_votes_share_bps))
+			{
+				return Err(VotingError::VoteShareExceeded);
+			}
+			Ok(())
+		}
+
+		// 内部辅助函数在票退回投票人时重新锁定之前投出的质押票
+		fn relock_staked_tickets(&mut self, owner: AccountId, returned: u128) {
+			let cast = *self.staked_tickets_cast.get(&owner).unwrap_or(&0);
+			let moved = cast.min(returned);
+			if moved == 0 {
+				return;
+			}
+			self.staked_tickets_cast.insert(owner, cast - moved);
+			let locked = self.staked_tickets_of(owner).saturating_add(moved);
+			self.staked_tickets.insert(owner, locked);
+		}
+
+		// 内部辅助函数获取投票人的代币权重, 未设置代币合约时权重为1
+		// 锁定的代币数量除以token_weight_divisor, 不超过max_token_weight
+		fn token_weight(&self, owner: AccountId) -> Result<u128> {
+			if self.token_contract.is_none() {
+				return Ok(1);
+			}
+			match (self.locked_tokens_of(owner) / self.token_weight_divisor)
+				.min(self.max_token_weight)
+			{
+				0 => Err(VotingError::NoTokenBalance),
+				weight => Ok(weight),
+			}
+		}
+
+		// 内部辅助函数调用代币合约的transfer_from, 把from的代币转给to
+		#[cfg(not(test))]
+		fn token_transfer_from(
+			token: AccountId,
+			from: AccountId,
+			to: AccountId,
+			value: Balance,
+		) -> Result<()> {
+			use ink_env::call::{build_call, utils::ReturnType, ExecutionInput, Selector};
+			build_call::<Environment>()
+				.callee(token)
+				.gas_limit(0)
+				.transferred_value(0)
+				.exec_input(
+					ExecutionInput::new(Selector::new(PSP22_TRANSFER_FROM_SELECTOR))
+						.push_arg(from)
+						.push_arg(to)
+						.push_arg(value)
+						.push_arg(Vec::<u8>::new()),
+				)
+				.returns::<ReturnType<core::result::Result<(), PSP22Error>>>()
+				.fire()
+				.map_err(|_| VotingError::TokenCallFailed)?
+				.map_err(|_| VotingError::TokenCallFailed)
+		}
+
+		// 内部辅助函数调用代币合约的transfer, 把本合约的代币转给to
+		#[cfg(not(test))]
+		fn token_transfer(token: AccountId, to: AccountId, value: Balance) -> Result<()> {
+			use ink_env::call::{build_call, utils::ReturnType, ExecutionInput, Selector};
+			build_call::<Environment>()
+				.callee(token)
+				.gas_limit(0)
+				.transferred_value(0)
+				.exec_input(
+					ExecutionInput::new(Selector::new(PSP22_TRANSFER_SELECTOR))
+						.push_arg(to)
+						.push_arg(value)
+						.push_arg(Vec::<u8>::new()),
+				)
+				.returns::<ReturnType<core::result::Result<(), PSP22Error>>>()
+				.fire()
+				.map_err(|_| VotingError::TokenCallFailed)?
+				.map_err(|_| VotingError::TokenCallFailed)
+		}
+
+		// 内部辅助函数调用闪电投票的回调合约, 回调返回false时视为失败
+		#[cfg(not(test))]
+		fn invoke_flash_hook(
+			&self,
+			hook: AccountId,
+			voter: AccountId,
+			candidate: AccountId,
+			amount: u128,
+		) -> Result<()> {
+			use ink_env::call::{build_call, utils::ReturnType, ExecutionInput, Selector};
+			let accepted = build_call::<Environment>()
+				.callee(hook)
+				.gas_limit(0)
+				.transferred_value(0)
+				.exec_input(
+					ExecutionInput::new(Selector::new(FLASH_HOOK_SELECTOR))
+						.push_arg(voter)
+						.push_arg(candidate)
+						.push_arg(amount),
+				)
+				.returns::<ReturnType<bool>>()
+				.fire()
+				.map_err(|_| VotingError::FlashHookFailed)?;
+			if !accepted {
+				return Err(VotingError::FlashHookFailed);
+			}
+			Ok(())
+		}
+
+		// 链下测试环境不支持跨合约调用, 改为调用测试中的模拟回调
+		// 模拟回调会记录回调时候选人的票数, 用来确认投票在回调期间生效
+		#[cfg(test)]
+		fn invoke_flash_hook(
+			&self,
+			hook: AccountId,
+			voter: AccountId,
+			candidate: AccountId,
+			amount: u128,
+		) -> Result<()> {
+			let votes = self.my_value_or_zero(candidate);
+			tests::mock_flash_hook(hook, voter, candidate, amount, votes)
+		}
+
+		// 链下测试环境中直接修改模拟余额, 余额不足时和代币合约一样返回错误
+		#[cfg(test)]
+		fn token_transfer_from(
+			token: AccountId,
+			from: AccountId,
+			to: AccountId,
+			value: Balance,
+		) -> Result<()> {
+			tests::mock_transfer(token, from, to, value)
+		}
+
+		#[cfg(test)]
+		fn token_transfer(token: AccountId, to: AccountId, value: Balance) -> Result<()> {
+			let contract = ink_env::account_id::<Environment>().expect("cannot get contract id");
+			tests::mock_transfer(token, contract, to, value)
+		}
+
+		// 内部辅助函数用户确认某用户是否被存在candidate_list中
+		fn valid_candidate(&self, candidate: AccountId) -> bool {
+			for x in self.candidate_list.into_iter() {
+				if *x == candidate {
+					return true;
+				}
+			}
+			return false;
+		}
+
+		// 内部辅助函数用户获取某用户的投票数量
+		fn my_value_or_zero(&self, of: AccountId) -> u128 {
+			let value = self.votes_received.get(&of).unwrap_or(&0);
+			*value
+		}
+	}
+
+	impl VotingCore for Voting {
+		#[ink(message)]
+		fn total_votes_for(&self, candidate: AccountId) -> u128 {
+			self.my_value_or_zero(candidate)
+		}
+
+		#[ink(message)]
+		fn get_candidates(&self) -> Vec<AccountId> {
+			self.candidate_list.iter().copied().collect()
+		}
+
+		// 调用者用自己手中的票给candidate投票
+		#[ink(message)]
+		fn vote_candidate(&mut self, candidate: AccountId, amount: u128) -> bool {
+			let owner = self.env().caller();
+			Voting::vote_candidate(self, owner, candidate, amount).is_ok()
+		}
+
+		#[ink(message)]
+		fn get_current_votes(&self) -> Vec<(AccountId, u128)> {
+			self.candidate_list
+				.iter()
+				.map(|x| (*x, self.my_value_or_zero(*x)))
+				.collect()
+		}
+	}
+
+	impl VoteProxy for Voting {
+		// 只有登记过的代理合约可以调用
+		#[ink(message)]
+		fn proxy_vote(&mut self, owner: AccountId, candidate: AccountId, amount: u128) -> bool {
+			if self.proxy_contract != Some(self.env().caller()) {
+				return false;
+			}
+			self.cast_vote(owner, candidate, amount).is_ok()
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use ink_env::test;
+		use ink_prelude::vec::Vec;
+		use ink_storage::collections::Vec as StorageVec;
+		use std::{cell::RefCell, collections::HashMap};
+		type Accounts = test::DefaultAccounts<Environment>;
+		fn default_accounts() -> Accounts {
+			test::default_accounts().expect("Test environment is expected to be initialized.")
+		}
+		thread_local! {
+			// 模拟代币合约中的余额, 键为(代币合约, 账户)
+			static MOCK_BALANCES: RefCell<HashMap<(AccountId, AccountId), Balance>> =
+				RefCell::new(HashMap::new());
+			// 拒绝闪电投票回调的模拟回调合约
+			static MOCK_REJECTING_HOOKS: RefCell<Vec<AccountId>> = RefCell::new(Vec::new());
+			// 模拟回调收到的调用, (回调合约, 投票人, 候选人, 票数, 回调时候选人的票数)
+			static MOCK_HOOK_CALLS: RefCell<Vec<(AccountId, AccountId, AccountId, u128, u128)>> =
+				RefCell::new(Vec::new());
+		}
+		pub(super) fn mock_flash_hook(
+			hook: AccountId,
+			voter: AccountId,
+			candidate: AccountId,
+			amount: u128,
+			votes: u128,
+		) -> Result<()> {
+			MOCK_HOOK_CALLS.with(|x| x.borrow_mut().push((hook, voter, candidate, amount, votes)));
+			if MOCK_REJECTING_HOOKS.with(|x| x.borrow().contains(&hook)) {
+				return Err(VotingError::FlashHookFailed);
+			}
+			Ok(())
+		}
+		fn mock_hook_calls() -> Vec<(AccountId, AccountId, AccountId, u128, u128)> {
+			MOCK_HOOK_CALLS.with(|x| x.borrow().clone())
+		}
+		pub(super) fn mock_balance_of(token: AccountId, owner: AccountId) -> Balance {
+			MOCK_BALANCES.with(|x| *x.borrow().get(&(token, owner)).unwrap_or(&0))
+		}
+		fn set_mock_balance(token: AccountId, owner: AccountId, value: Balance) {
+			MOCK_BALANCES.with(|x| x.borrow_mut().insert((token, owner), value));
+		}
+		pub(super) fn mock_transfer(
+			token: AccountId,
+			from: AccountId,
+			to: AccountId,
+			value: Balance,
+		) -> Result<()> {
+			let from_balance = mock_balance_of(token, from)
+				.checked_sub(value)
+				.ok_or(VotingError::TokenCallFailed)?;
+			set_mock_balance(token, from, from_balance);
+			set_mock_balance(token, to, mock_balance_of(token, to) + value);
+			Ok(())
+		}
+		fn set_caller_with_value(caller: AccountId, value: Balance) {
+			let callee =
+				ink_env::account_id::<ink_env::DefaultEnvironment>().unwrap_or([0x0; 32].into());
+			test::push_execution_context::<Environment>(
+				caller,
+				callee,
+				1000000,
+				value,
+				test::CallData::new(ink_env::call::Selector::new([0x00; 4])),
+			);
+		}
+		// 链下测试环境中转账不会进入合约账户, 需要先给合约账户充值才能退款
+		fn fund_contract(value: Balance) {
+			let contract = ink_env::account_id::<Environment>().expect("cannot get contract id");
+			test::set_account_balance::<Environment>(contract, value)
+				.expect("cannot set contract balance");
+		}
+		fn account_balance(account: AccountId) -> Balance {
+			test::get_account_balance::<Environment>(account).expect("cannot get account balance")
+		}
+		// 用alice, bob, charlie, django构造一棵默克尔树, 返回根和每个叶子的证明
+		fn merkle_tree(accounts: &Accounts) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+			let leaves: Vec<_> = ink_prelude::vec![
+				accounts.alice,
+				accounts.bob,
+				accounts.charlie,
+				accounts.django
+			]
+			.into_iter()
+			.map(Voting::merkle_leaf)
+			.collect();
+			let left = Voting::merkle_parent(&leaves[0], &leaves[1]);
+			let right = Voting::merkle_parent(&leaves[2], &leaves[3]);
+			let root = Voting::merkle_parent(&left, &right);
+			let proofs = ink_prelude::vec![
+				ink_prelude::vec![leaves[1], right],
+				ink_prelude::vec![leaves[0], right],
+				ink_prelude::vec![leaves[3], left],
+				ink_prelude::vec![leaves[2], left],
+			];
+			(root, proofs)
+		}
+		fn default_config() -> VotingConfig {
+			VotingConfig {
+				total_tokens: 100,
+				max_supply: u128::MAX,
+				token_price: 1,
+				min_price: 1,
+				duration_ms: 1000,
+				mode: VotingMode::Linear,
+				enable_whitelist: false,
+				merkle_root: [0; 32],
+				max_snapshots: 10,
+				max_votes_per_candidate: u128::MAX,
+				seats: 1,
+				max_votes_per_voter_per_candidate: u128::MAX,
+				min_votes_threshold: 0,
+				max_tickets_per_voter: u128::MAX,
+				time_weighted: false,
+				metadata: String::new(),
+				token_contract: None,
+				vote_expiry_blocks: 0,
+				veto_threshold_bps: 0,
+				quorum_votes: 0,
+				require_payment: false,
+				max_history_blocks: 0,
+				auction_step: 0,
+				election_id: 0,
+				conviction_period: 0,
+				max_conviction_multiplier: 1,
+				warning_window_ms: 0,
+				decay_rate_bps_per_block: 0,
+				stake_token: None,
+				fee_bps: 0,
+				fee_treasury: [0xff; 32].into(),
+				flash_hook_contract: None,
+				max_votes_share_bps: 0,
+				min_votes_for_share_cap: 0,
+				token_weight_divisor: 1,
+				max_token_weight: u128::MAX,
+			}
+		}
+		fn set_caller(caller: AccountId) {
+			let callee =
+				ink_env::account_id::<ink_env::DefaultEnvironment>().unwrap_or([0x0; 32].into());
+			test::push_execution_context::<Environment>(
+				caller,
+				callee,
+				1000000,
+				1000000,
+				test::CallData::new(ink_env::call::Selector::new([0x00; 4])),
+			);
+		}
+		#[test]
+		fn default_works() {
+			let mut voting = Voting::new(Vec::new(), default_config());
+			assert_eq!(voting.candidate_list, StorageVec::new());
+			assert_eq!(voting.candidate_list.len(), 0);
+			assert_eq!(voting.get_candidates_len(), 0);
+			assert_eq!(voting.all_ticket_num(), 100);
+			assert_eq!(voting.left_ticket_num(), 100);
+			assert_eq!(voting.price_of_ticket(), 1);
+		}
+
+		#[test]
+		fn is_candidate_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert!(voting.is_candidate(accounts.alice));
+			assert!(voting.is_candidate(accounts.bob));
+			assert!(!voting.is_candidate(accounts.eve));
+			voting.remove_candidate(accounts.bob).unwrap();
+			assert!(!voting.is_candidate(accounts.bob));
+			let voting = Voting::new(Vec::new(), default_config());
+			assert!(!voting.is_candidate(accounts.alice));
+		}
+
+		#[test]
+		fn init_candidates() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.candidate_list.len(), 3);
+			assert_eq!(voting.all_ticket_num(), 100);
+			assert_eq!(voting.left_ticket_num(), 100);
+			assert_eq!(voting.price_of_ticket(), 1);
+		}
+
+		#[test]
+		fn buy_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.left_ticket_num(), 100);
+			voting.buy_ticket(accounts.alice, 1).unwrap();
+			assert_eq!(voting.left_ticket_num(), 99);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 1);
+			voting.buy_ticket(accounts.alice, 1).unwrap();
+			assert_eq!(voting.left_ticket_num(), 98);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 2);
+			voting.buy_ticket(accounts.bob, 1).unwrap();
+			assert_eq!(voting.left_ticket_num(), 97);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 2);
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 1);
+		}
+
+		#[test]
+		fn voter_balance_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+		}
+
+		#[test]
+		fn vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 9);
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 1);
+		}
+
+		#[test]
+		fn vote_invalid_candidate_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.eve, 1)
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+		}
+
+		#[test]
+		fn ticket_not_enough_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 1).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+					.unwrap_err(),
+				VotingError::InsufficientTickets
+			);
+		}
+
+		#[test]
+		fn anyone_could_buy_ticket() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.eve, 10).unwrap();
+		}
+
+		#[test]
+		fn buy_more_than_left_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 10,
+					..default_config()
+				},
+			);
+			assert_eq!(
+				voting.buy_ticket(accounts.eve, 11).unwrap_err(),
+				VotingError::SoldOut
+			);
+			assert_eq!(voting.left_ticket_num(), 10);
+			assert_eq!(voting.voter_ticket_balance(accounts.eve), 0);
+		}
+
+		#[test]
+		fn buy_ticket_keeps_dust() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 3,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 7).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 2);
+			assert_eq!(voting.claim_dust(), 1);
+			voting.buy_ticket(accounts.alice, 5).unwrap();
+			voting.buy_ticket(accounts.alice, 8).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+			assert_eq!(voting.claim_dust(), 4);
+		}
+
+		#[test]
+		fn claim_dust_twice_returns_zero() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 3,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 8).unwrap();
+			assert_eq!(voting.claim_dust(), 2);
+			assert_eq!(voting.claim_dust(), 0);
+		}
+
+		#[test]
+		fn whitelist_blocks_unknown_voters() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let (root, proofs) = merkle_tree(&accounts);
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					enable_whitelist: true,
+					merkle_root: root,
+					..default_config()
+				},
+			);
+			assert!(voting.is_whitelist_enabled());
+			assert_eq!(voting.get_merkle_root(), root);
+			// 开启白名单后必须带证明买票
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 10).unwrap_err(),
+				VotingError::NotWhitelisted
+			);
+			assert!(voting.is_whitelisted(accounts.alice, proofs[0].clone()));
+			voting
+				.buy_ticket_with_proof(accounts.alice, 10, proofs[0].clone())
+				.unwrap();
+			voting
+				.buy_ticket_with_proof(accounts.django, 5, proofs[3].clone())
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(voting.voter_ticket_balance(accounts.django), 5);
+		}
+
+		#[test]
+		fn whitelist_invalid_proof_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let (root, proofs) = merkle_tree(&accounts);
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					enable_whitelist: true,
+					merkle_root: root,
+					..default_config()
+				},
+			);
+			// 不在白名单中的用户不能借用别人的证明
+			assert!(!voting.is_whitelisted(accounts.eve, proofs[0].clone()));
+			assert_eq!(
+				voting
+					.buy_ticket_with_proof(accounts.eve, 10, proofs[0].clone())
+					.unwrap_err(),
+				VotingError::NotWhitelisted
+			);
+			assert_eq!(
+				voting
+					.buy_ticket_with_proof(accounts.alice, 10, proofs[1].clone())
+					.unwrap_err(),
+				VotingError::NotWhitelisted
+			);
+			assert_eq!(
+				voting
+					.buy_ticket_with_proof(accounts.alice, 10, Vec::new())
+					.unwrap_err(),
+				VotingError::NotWhitelisted
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.eve), 0);
+		}
+
+		#[test]
+		fn set_merkle_root_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let (root, proofs) = merkle_tree(&accounts);
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					enable_whitelist: true,
+					..default_config()
+				},
+			);
+			assert!(!voting.is_whitelisted(accounts.alice, proofs[0].clone()));
+			voting.set_merkle_root(root).unwrap();
+			assert!(voting.is_whitelisted(accounts.alice, proofs[0].clone()));
+			// 只有一个用户时根就是叶子, 证明为空
+			voting
+				.set_merkle_root(Voting::merkle_leaf(accounts.eve))
+				.unwrap();
+			assert!(!voting.is_whitelisted(accounts.alice, proofs[0].clone()));
+			voting
+				.buy_ticket_with_proof(accounts.eve, 10, Vec::new())
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.eve), 10);
+		}
+
+		#[test]
+		fn toggle_whitelist_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert!(!voting.is_whitelist_enabled());
+			voting.buy_ticket(accounts.eve, 10).unwrap();
+			voting.set_whitelist_enabled(true).unwrap();
+			assert_eq!(
+				voting.buy_ticket(accounts.eve, 10).unwrap_err(),
+				VotingError::NotWhitelisted
+			);
+			voting.set_whitelist_enabled(false).unwrap();
+			voting.buy_ticket(accounts.eve, 10).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.eve), 20);
+		}
+
+		#[test]
+		fn whitelist_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					enable_whitelist: true,
+					..default_config()
+				},
+			);
+			set_caller(accounts.eve);
+			assert_eq!(
+				voting
+					.set_merkle_root(Voting::merkle_leaf(accounts.eve))
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(
+				voting.set_whitelist_enabled(false).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert!(!voting.is_whitelisted(accounts.eve, Vec::new()));
+		}
+
+		#[test]
+		fn advance_phase_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.current_phase(), VotingPhase::Registration);
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.current_phase(), VotingPhase::Active);
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.current_phase(), VotingPhase::Ended);
+			assert_eq!(voting.advance_phase().unwrap_err(), VotingError::WrongPhase);
+			assert_eq!(voting.current_phase(), VotingPhase::Ended);
+		}
+
+		#[test]
+		fn advance_phase_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.eve);
+			assert_eq!(
+				voting.advance_phase().unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.current_phase(), VotingPhase::Registration);
+		}
+
+		#[test]
+		fn wrong_phase_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			test::set_block_timestamp::<Environment>(0);
+			let mut voting = Voting::new(candidates, default_config());
+			// 阶段只由管理员推进, 与时间无关
+			test::set_block_timestamp::<Environment>(500);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::WrongPhase
+			);
+			assert_eq!(voting.get_winner().unwrap_err(), VotingError::WrongPhase);
+
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 10).unwrap_err(),
+				VotingError::WrongPhase
+			);
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(voting.get_winner().unwrap_err(), VotingError::WrongPhase);
+
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::WrongPhase
+			);
+			assert_eq!(
+				voting
+					.revoke_vote(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::WrongPhase
+			);
+			assert_eq!(voting.get_winner(), Ok(Some(accounts.bob)));
+		}
+
+		#[test]
+		fn pause_blocks_buy_and_vote() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.pause().unwrap();
+			assert!(voting.is_paused());
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 10).unwrap_err(),
+				VotingError::ContractPaused
+			);
+			assert_eq!(
+				voting
+					.vote_candidate(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::ContractPaused
+			);
+			voting.unpause().unwrap();
+			assert!(!voting.is_paused());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 19);
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+		}
+
+		#[test]
+		fn pause_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.eve);
+			assert_eq!(voting.pause().unwrap_err(), VotingError::Unauthorized);
+			assert!(!voting.is_paused());
+			set_caller(accounts.alice);
+			voting.pause().unwrap();
+			set_caller(accounts.eve);
+			assert_eq!(voting.unpause().unwrap_err(), VotingError::Unauthorized);
+			assert!(voting.is_paused());
+		}
+
+		#[test]
+		fn batch_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			let results = voting.batch_vote(
+				accounts.alice,
+				ink_prelude::vec![(accounts.bob, 3), (accounts.eve, 2)],
+			);
+			assert_eq!(results, ink_prelude::vec![Ok(()), Ok(())]);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.total_votes_for(accounts.eve), 2);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+		}
+
+		#[test]
+		fn batch_vote_partially_succeeds() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			let results = voting.batch_vote(
+				accounts.alice,
+				ink_prelude::vec![
+					(accounts.bob, 4),
+					(accounts.eve, 1),
+					(accounts.alice, 7),
+					(accounts.alice, 6)
+				],
+			);
+			assert_eq!(
+				results,
+				ink_prelude::vec![
+					Ok(()),
+					Err(VotingError::NotACandidate),
+					Err(VotingError::InsufficientTickets),
+					Ok(())
+				]
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 4);
+			assert_eq!(voting.total_votes_for(accounts.alice), 6);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+		}
+
+		#[test]
+		fn set_token_price_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			voting.set_token_price(5).unwrap();
+			assert_eq!(voting.price_of_ticket(), 5);
+			// 改价前买的票数不变
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 10);
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.charlie), 2);
+			assert_eq!(voting.left_ticket_num(), 88);
+		}
+
+		#[test]
+		fn set_token_price_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.set_token_price(5).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.price_of_ticket(), 1);
+		}
+
+		#[test]
+		fn set_token_price_below_minimum_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 5,
+					min_price: 2,
+					..default_config()
+				},
+			);
+			assert_eq!(
+				voting.set_token_price(1).unwrap_err(),
+				VotingError::PriceBelowMinimum
+			);
+			assert_eq!(
+				voting.set_token_price(0).unwrap_err(),
+				VotingError::PriceBelowMinimum
+			);
+			assert_eq!(voting.price_of_ticket(), 5);
+			voting.set_token_price(2).unwrap();
+			assert_eq!(voting.price_of_ticket(), 2);
+		}
+
+		#[test]
+		fn refund_tickets_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 10,
+					token_price: 2,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			assert_eq!(voting.left_ticket_num(), 0);
+			voting.refund_tickets(accounts.alice, 4).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 6);
+			assert_eq!(voting.left_ticket_num(), 4);
+			// 退回的票可以被重新购买
+			voting.buy_ticket(accounts.bob, 8).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 4);
+			assert_eq!(voting.left_ticket_num(), 0);
+			assert_eq!(voting.claim_refund(), 8);
+			assert_eq!(voting.claim_refund(), 0);
+		}
+
+		#[test]
+		fn refund_tickets_uses_current_price() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.refund_tickets(accounts.alice, 2).unwrap();
+			voting.set_token_price(3).unwrap();
+			voting.refund_tickets(accounts.alice, 2).unwrap();
+			assert_eq!(voting.claim_refund(), 8);
+		}
+
+		#[test]
+		fn refund_tickets_by_other_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.refund_tickets(accounts.alice, 2).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(voting.claim_refund(), 0);
+		}
+
+		#[test]
+		fn refund_too_many_tickets_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			assert_eq!(
+				voting.refund_tickets(accounts.alice, 11).unwrap_err(),
+				VotingError::InsufficientTickets
+			);
+			assert_eq!(
+				voting.refund_tickets(accounts.alice, 0).unwrap_err(),
+				VotingError::ZeroAmount
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(voting.left_ticket_num(), 90);
+		}
+
+		#[test]
+		fn vote_up_to_candidate_cap_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_per_candidate: 5,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+		}
+
+		#[test]
+		fn vote_over_candidate_cap_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_per_candidate: 5,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			// 超过上限的部分不会被部分接受
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+					.unwrap_err(),
+				VotingError::CandidateCapExceeded
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 7);
+		}
+
+		#[test]
+		fn split_votes_under_candidate_cap_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_per_candidate: 5,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 8)
+					.unwrap_err(),
+				VotingError::CandidateCapExceeded
+			);
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 4)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 4);
+			assert_eq!(voting.total_votes_for(accounts.alice), 4);
+		}
+
+		#[test]
+		fn get_voter_history_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_voter_history(accounts.alice), Vec::new());
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.bob, accounts.alice, 4)
+				.unwrap();
+			assert_eq!(
+				voting.get_voter_history(accounts.alice),
+				ink_prelude::vec![(accounts.charlie, 5), (accounts.bob, 1)]
+			);
+			assert_eq!(
+				voting.get_voter_history(accounts.bob),
+				ink_prelude::vec![(accounts.alice, 4)]
+			);
+			assert_eq!(voting.get_voter_history(accounts.eve), Vec::new());
+		}
+
+		#[test]
+		fn get_voter_history_skips_revoked_votes() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+				.unwrap();
+			voting.revoke_vote(accounts.alice, accounts.bob, 2).unwrap();
+			assert_eq!(
+				voting.get_voter_history(accounts.alice),
+				ink_prelude::vec![(accounts.alice, 1)]
+			);
+			// 重新投票不会重复记录
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(
+				voting.get_voter_history(accounts.alice),
+				ink_prelude::vec![(accounts.bob, 3), (accounts.alice, 1)]
+			);
+		}
+
+		#[test]
+		fn mint_tokens_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 10,
+					max_supply: 30,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			assert_eq!(
+				voting.buy_ticket(accounts.bob, 5).unwrap_err(),
+				VotingError::SoldOut
+			);
+			voting.mint_tokens(20).unwrap();
+			assert_eq!(voting.all_ticket_num(), 30);
+			assert_eq!(voting.left_ticket_num(), 20);
+			// 增发的票可以马上被购买
+			voting.buy_ticket(accounts.bob, 5).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 5);
+			assert_eq!(voting.left_ticket_num(), 15);
+		}
+
+		#[test]
+		fn mint_tokens_over_max_supply_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 10,
+					max_supply: 30,
+					..default_config()
+				},
+			);
+			assert_eq!(
+				voting.mint_tokens(21).unwrap_err(),
+				VotingError::MaxSupplyExceeded
+			);
+			assert_eq!(
+				voting.mint_tokens(u128::MAX).unwrap_err(),
+				VotingError::MaxSupplyExceeded
+			);
+			assert_eq!(voting.all_ticket_num(), 10);
+			assert_eq!(voting.left_ticket_num(), 10);
+		}
+
+		#[test]
+		fn mint_tokens_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.mint_tokens(10).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.all_ticket_num(), 100);
+		}
+
+		#[test]
+		fn get_winners_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![
+				accounts.alice,
+				accounts.bob,
+				accounts.charlie,
+				accounts.django
+			];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					seats: 2,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.django, 1)
+				.unwrap();
+			assert_eq!(voting.get_winners().unwrap_err(), VotingError::WrongPhase);
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.get_winners().unwrap(),
+				ink_prelude::vec![accounts.charlie, accounts.bob]
+			);
+		}
+
+		#[test]
+		fn get_winners_includes_ties_at_last_seat() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![
+				accounts.alice,
+				accounts.bob,
+				accounts.charlie,
+				accounts.django
+			];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					seats: 2,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.django, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 3)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.get_winners().unwrap(),
+				ink_prelude::vec![accounts.bob, accounts.charlie, accounts.django]
+			);
+		}
+
+		#[test]
+		fn get_winners_with_fewer_candidates_than_seats_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					seats: 5,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.get_winners().unwrap(),
+				ink_prelude::vec![accounts.bob, accounts.alice]
+			);
+		}
+
+		#[test]
+		fn get_winners_with_zero_seats_is_empty() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					seats: 0,
+					..default_config()
+				},
+			);
+			voting.advance_phase().unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_winners().unwrap(), Vec::new());
+		}
+
+		#[test]
+		fn voter_cap_holds_across_votes() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_per_voter_per_candidate: 5,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+					.unwrap_err(),
+				VotingError::ExceedsVoterCap
+			);
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::ExceedsVoterCap
+			);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 5);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 15);
+			// 投给其他候选人不受影响
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 5)
+				.unwrap();
+		}
+
+		#[test]
+		fn voter_cap_is_per_voter() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_per_voter_per_candidate: 5,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.buy_ticket(accounts.charlie, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.bob, 5)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 10);
+		}
+
+		#[test]
+		fn reset_election_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.alice, 4)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			voting.reset_election().unwrap();
+			assert_eq!(voting.current_phase(), VotingPhase::Registration);
+			assert_eq!(voting.total_votes_for(accounts.alice), 0);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 0);
+			assert_eq!(voting.get_voter_history(accounts.alice), Vec::new());
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.charlie), 0);
+			assert_eq!(voting.left_ticket_num(), 100);
+			// 候选人列表不变
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![accounts.alice, accounts.bob]
+			);
+			// 可以重新买票和投票
+			voting.buy_ticket(accounts.alice, 5).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+			assert_eq!(voting.left_ticket_num(), 95);
+		}
+
+		#[test]
+		fn reset_election_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.reset_election().unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(voting.left_ticket_num(), 90);
+		}
+
+		#[test]
+		fn ranked_choice_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.advance_phase().unwrap();
+			let ballots = [
+				ink_prelude::vec![accounts.charlie, accounts.bob],
+				ink_prelude::vec![accounts.charlie, accounts.bob],
+				ink_prelude::vec![accounts.charlie],
+				ink_prelude::vec![accounts.charlie, accounts.alice],
+				ink_prelude::vec![accounts.alice, accounts.bob],
+				ink_prelude::vec![accounts.alice, accounts.charlie],
+				ink_prelude::vec![accounts.alice],
+				ink_prelude::vec![accounts.bob, accounts.alice],
+				ink_prelude::vec![accounts.bob, accounts.alice, accounts.charlie],
+			];
+			for (i, ballot) in ballots.iter().enumerate() {
+				let voter = AccountId::from([i as u8 + 1; 32]);
+				set_caller(voter);
+				voting.ranked_choice_vote(voter, ballot.clone()).unwrap();
+			}
+			set_caller(accounts.alice);
+			voting.advance_phase().unwrap();
+			// 第一轮charlie 4票, alice 3票, bob 2票, 都没有过半
+			// 淘汰bob后他的选票转给alice, alice 5票过半获胜
+			assert_eq!(voting.compute_irv_winner().unwrap(), Some(accounts.alice));
+		}
+
+		#[test]
+		fn compute_irv_winner_with_first_round_majority() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.advance_phase().unwrap();
+			voting
+				.ranked_choice_vote(accounts.alice, ink_prelude::vec![accounts.bob])
+				.unwrap();
+			set_caller(accounts.bob);
+			voting
+				.ranked_choice_vote(
+					accounts.bob,
+					ink_prelude::vec![accounts.bob, accounts.alice],
+				)
+				.unwrap();
+			set_caller(accounts.charlie);
+			voting
+				.ranked_choice_vote(accounts.charlie, ink_prelude::vec![accounts.charlie])
+				.unwrap();
+			set_caller(accounts.alice);
+			assert_eq!(
+				voting.compute_irv_winner().unwrap_err(),
+				VotingError::WrongPhase
+			);
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.compute_irv_winner().unwrap(), Some(accounts.bob));
+		}
+
+		#[test]
+		fn compute_condorcet_winner_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.django, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.compute_condorcet_winner(), None);
+			// bob在第一偏好中票数最少, 但两两比较都能胜出
+			let ballots = [
+				ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie],
+				ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie],
+				ink_prelude::vec![accounts.charlie, accounts.bob, accounts.alice],
+				ink_prelude::vec![accounts.charlie, accounts.bob, accounts.alice],
+				ink_prelude::vec![accounts.bob, accounts.alice],
+			];
+			for (i, ballot) in ballots.iter().enumerate() {
+				let voter = AccountId::from([i as u8 + 1; 32]);
+				set_caller(voter);
+				voting.ranked_choice_vote(voter, ballot.clone()).unwrap();
+			}
+			set_caller(accounts.alice);
+			assert_eq!(voting.get_pairwise_votes(accounts.bob, accounts.alice), 3);
+			assert_eq!(voting.get_pairwise_votes(accounts.alice, accounts.bob), 2);
+			assert_eq!(voting.get_pairwise_votes(accounts.bob, accounts.charlie), 3);
+			assert_eq!(voting.compute_condorcet_winner(), Some(accounts.bob));
+			// 普通投票和撤回都不影响两两比较
+			voting
+				.vote_candidate_without_event(accounts.django, accounts.alice, 2)
+				.unwrap();
+			assert_eq!(voting.get_pairwise_votes(accounts.alice, accounts.bob), 2);
+			assert_eq!(voting.compute_condorcet_winner(), Some(accounts.bob));
+			set_caller(accounts.django);
+			voting
+				.revoke_vote(accounts.django, accounts.alice, 2)
+				.unwrap();
+			assert_eq!(voting.get_pairwise_votes(accounts.alice, accounts.bob), 2);
+			assert_eq!(voting.compute_condorcet_winner(), Some(accounts.bob));
+			// 被移除的候选人不再参与两两比较
+			set_caller(accounts.alice);
+			voting.remove_candidate(accounts.alice).unwrap();
+			assert_eq!(voting.get_pairwise_votes(accounts.bob, accounts.alice), 0);
+			assert_eq!(voting.get_pairwise_votes(accounts.bob, accounts.charlie), 3);
+			assert_eq!(voting.get_pairwise_votes(accounts.charlie, accounts.bob), 2);
+			assert_eq!(voting.compute_condorcet_winner(), Some(accounts.bob));
+		}
+
+		#[test]
+		fn compute_condorcet_winner_with_cycle_is_none() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.advance_phase().unwrap();
+			// alice胜bob, bob胜charlie, charlie胜alice, 都是2比1
+			let ballots = [
+				ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie],
+				ink_prelude::vec![accounts.bob, accounts.charlie, accounts.alice],
+				ink_prelude::vec![accounts.charlie, accounts.alice, accounts.bob],
+			];
+			for (i, ballot) in ballots.iter().enumerate() {
+				let voter = AccountId::from([i as u8 + 1; 32]);
+				set_caller(voter);
+				voting.ranked_choice_vote(voter, ballot.clone()).unwrap();
+			}
+			set_caller(accounts.alice);
+			assert_eq!(voting.get_pairwise_votes(accounts.alice, accounts.bob), 2);
+			assert_eq!(voting.get_pairwise_votes(accounts.bob, accounts.charlie), 2);
+			assert_eq!(
+				voting.get_pairwise_votes(accounts.charlie, accounts.alice),
+				2
+			);
+			assert_eq!(voting.compute_condorcet_winner(), None);
+			// 重复提交的选票覆盖之前的选票
+			set_caller(AccountId::from([3; 32]));
+			voting
+				.ranked_choice_vote(
+					AccountId::from([3; 32]),
+					ink_prelude::vec![accounts.alice, accounts.charlie],
+				)
+				.unwrap();
+			assert_eq!(
+				voting.get_pairwise_votes(accounts.charlie, accounts.alice),
+				1
+			);
+			assert_eq!(voting.compute_condorcet_winner(), Some(accounts.alice));
+		}
+
+		#[test]
+		fn compute_irv_winner_without_ballots_is_none() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.advance_phase().unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.compute_irv_winner().unwrap(), None);
+		}
+
+		#[test]
+		fn invalid_ranked_ballot_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.ranked_choice_vote(accounts.alice, Vec::new())
+					.unwrap_err(),
+				VotingError::InvalidBallot
+			);
+			assert_eq!(
+				voting
+					.ranked_choice_vote(
+						accounts.alice,
+						ink_prelude::vec![accounts.bob, accounts.alice, accounts.bob]
+					)
+					.unwrap_err(),
+				VotingError::InvalidBallot
+			);
+			assert_eq!(
+				voting
+					.ranked_choice_vote(accounts.alice, ink_prelude::vec![accounts.eve])
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.compute_irv_winner().unwrap(), None);
+		}
+
+		#[test]
+		fn get_vote_share_bps_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(
+				voting.get_vote_share_bps(),
+				ink_prelude::vec![
+					(accounts.alice, 2500),
+					(accounts.bob, 7500),
+					(accounts.charlie, 0)
+				]
+			);
+		}
+
+		#[test]
+		fn get_vote_share_bps_rounds_down() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 1)
+				.unwrap();
+			let shares = voting.get_vote_share_bps();
+			assert!(shares.iter().all(|(_, share)| *share == 3333));
+			assert!(shares.iter().map(|(_, share)| share).sum::<u32>() <= 10_000);
+		}
+
+		#[test]
+		fn get_vote_share_bps_without_votes_is_zero() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.get_vote_share_bps(),
+				ink_prelude::vec![(accounts.alice, 0), (accounts.bob, 0)]
+			);
+			let voting = Voting::new(Vec::new(), default_config());
+			assert_eq!(voting.get_vote_share_bps(), Vec::new());
+		}
+
+		#[test]
+		fn transfer_admin_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.transfer_admin(accounts.bob).unwrap();
+			// 接受之前原管理员仍然有权限
+			assert_eq!(voting.get_admins(), ink_prelude::vec![accounts.alice]);
+			voting.add_candidate(accounts.charlie).unwrap();
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.add_candidate(accounts.django).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			voting.accept_admin().unwrap();
+			assert_eq!(voting.get_admins(), ink_prelude::vec![accounts.bob]);
+			assert_eq!(voting.pending_admin, None);
+			voting.add_candidate(accounts.django).unwrap();
+			set_caller(accounts.alice);
+			assert_eq!(
+				voting.add_candidate(accounts.eve).unwrap_err(),
+				VotingError::Unauthorized
+			);
+		}
+
+		#[test]
+		fn accept_admin_by_other_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.accept_admin().unwrap_err(),
+				VotingError::Unauthorized
+			);
+			voting.transfer_admin(accounts.bob).unwrap();
+			set_caller(accounts.charlie);
+			assert_eq!(
+				voting.accept_admin().unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(
+				voting.transfer_admin(accounts.charlie).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_admins(), ink_prelude::vec![accounts.alice]);
+			assert_eq!(voting.pending_admin, Some((accounts.alice, accounts.bob)));
+		}
+
+		#[test]
+		fn batch_buy_tickets_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 20,
+					token_price: 2,
+					max_tickets_per_voter: 10,
+					..default_config()
+				},
+			);
+			let results = voting.batch_buy_tickets(ink_prelude::vec![
+				(accounts.alice, 10),
+				(accounts.eve, 22),
+				(accounts.bob, 32),
+				(accounts.charlie, 20),
+			]);
+			assert_eq!(
+				results,
+				ink_prelude::vec![
+					Ok(()),
+					Err(VotingError::ExceedsTicketCap),
+					Err(VotingError::SoldOut),
+					Ok(()),
+				]
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+			assert_eq!(voting.voter_ticket_balance(accounts.eve), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.charlie), 10);
+			// 只有成功的项扣减剩余票数
+			assert_eq!(voting.left_ticket_num(), 5);
+		}
+
+		#[test]
+		fn min_votes_threshold_excludes_candidates() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					min_votes_threshold: 3,
+					seats: 3,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 2)
+				.unwrap();
+			assert_eq!(
+				voting.get_qualified_candidates(),
+				ink_prelude::vec![accounts.bob]
+			);
+			let top = voting.get_top_n_candidates(3);
+			assert_eq!(top.len(), 1);
+			assert_eq!(top[0].candidate, accounts.bob);
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_winner().unwrap(), Some(accounts.bob));
+			assert_eq!(
+				voting.get_winners().unwrap(),
+				ink_prelude::vec![accounts.bob]
+			);
+		}
+
+		#[test]
+		fn only_candidate_below_threshold_does_not_win() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					min_votes_threshold: 5,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_qualified_candidates(), Vec::new());
+			assert_eq!(voting.get_top_n_candidates(1).len(), 0);
+			assert_eq!(voting.get_winner().unwrap(), None);
+			assert_eq!(voting.get_winners().unwrap(), Vec::new());
+		}
+
+		#[test]
+		fn import_previous_votes_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting
+				.import_previous_votes(ink_prelude::vec![
+					VoteOfCandidate {
+						candidate: accounts.bob,
+						vote: 7,
+					},
+					VoteOfCandidate {
+						candidate: accounts.alice,
+						vote: 2,
+					},
+					VoteOfCandidate {
+						candidate: accounts.bob,
+						vote: 1,
+					},
+				])
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.alice), 2);
+			assert_eq!(voting.total_votes_for(accounts.bob), 8);
+			// 新的投票累加在导入的票数上
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.alice, 3)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.alice), 5);
+			assert_eq!(voting.total_votes_for(accounts.bob), 8);
+		}
+
+		#[test]
+		fn import_previous_votes_after_voting_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(
+				voting
+					.import_previous_votes(ink_prelude::vec![VoteOfCandidate {
+						candidate: accounts.alice,
+						vote: 5,
+					}])
+					.unwrap_err(),
+				VotingError::VotesAlreadyCast
+			);
+			assert_eq!(voting.total_votes_for(accounts.alice), 0);
+		}
+
+		#[test]
+		fn import_invalid_previous_votes_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			let legacy = ink_prelude::vec![
+				VoteOfCandidate {
+					candidate: accounts.alice,
+					vote: 5,
+				},
+				VoteOfCandidate {
+					candidate: accounts.eve,
+					vote: 5,
+				},
+			];
+			assert_eq!(
+				voting.import_previous_votes(legacy.clone()).unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert_eq!(voting.total_votes_for(accounts.alice), 0);
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.import_previous_votes(legacy).unwrap_err(),
+				VotingError::Unauthorized
+			);
+		}
+
+		#[test]
+		fn weighted_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_voter_weight(accounts.alice), 1);
+			voting.set_voter_weight(accounts.alice, 3).unwrap();
+			assert_eq!(voting.get_voter_weight(accounts.alice), 3);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			// 只扣除投出的票数
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 9);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 1);
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+			voting.revoke_vote(accounts.alice, accounts.bob, 1).unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 2);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+		}
+
+		#[test]
+		fn set_voter_weight_does_not_work_when_invalid() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.set_voter_weight(accounts.bob, 0).unwrap_err(),
+				VotingError::ZeroAmount
+			);
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.set_voter_weight(accounts.bob, 5).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			set_caller(accounts.alice);
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.set_voter_weight(accounts.bob, 5).unwrap_err(),
+				VotingError::WrongPhase
+			);
+			assert_eq!(voting.get_voter_weight(accounts.bob), 1);
+		}
+
+		#[test]
+		fn get_turnout_bps_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 40,
+					..default_config()
+				},
+			);
+			assert_eq!(voting.get_turnout_bps(), 0);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			// 买了票但还没投
+			assert_eq!(voting.get_turnout_bps(), 0);
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 10)
+				.unwrap();
+			assert_eq!(voting.get_turnout_bps(), 2500);
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(voting.get_turnout_bps(), 3250);
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.alice, 7)
+				.unwrap();
+			assert_eq!(voting.get_turnout_bps(), 5000);
+		}
+
+		#[test]
+		fn get_turnout_bps_with_zero_total_is_zero() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 0,
+					..default_config()
+				},
+			);
+			assert_eq!(voting.get_turnout_bps(), 0);
+		}
+
+		#[test]
+		fn buy_ticket_up_to_voter_cap_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_tickets_per_voter: 10,
+					..default_config()
+				},
+			);
+			assert_eq!(voting.get_max_tickets_per_voter(), 10);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 1).unwrap_err(),
+				VotingError::ExceedsTicketCap
+			);
+		}
+
+		#[test]
+		fn buy_ticket_over_voter_cap_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_tickets_per_voter: 10,
+					..default_config()
+				},
+			);
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 11).unwrap_err(),
+				VotingError::ExceedsTicketCap
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.left_ticket_num(), 100);
+		}
+
+		#[test]
+		fn buy_ticket_accumulates_to_voter_cap() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_tickets_per_voter: 10,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 4).unwrap();
+			voting.buy_ticket(accounts.alice, 4).unwrap();
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 4).unwrap_err(),
+				VotingError::ExceedsTicketCap
+			);
+			voting.buy_ticket(accounts.alice, 2).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			// 其他投票人不受影响
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			assert_eq!(voting.left_ticket_num(), 80);
+		}
+
+		#[test]
+		fn time_weighted_vote_favours_early_votes() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			test::set_block_timestamp::<Environment>(0);
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					time_weighted: true,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 2)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.alice), 4);
+			test::set_block_timestamp::<Environment>(500);
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			test::set_block_timestamp::<Environment>(1000);
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+			// 未加权票数单独记录
+			assert_eq!(voting.raw_votes_for(accounts.alice), 2);
+			assert_eq!(voting.raw_votes_for(accounts.bob), 4);
+			assert_eq!(voting.voter_ticket_balance(accounts.charlie), 6);
+			assert_eq!(
+				voting
+					.revoke_vote(accounts.alice, accounts.alice, 1)
+					.unwrap_err(),
+				VotingError::RevokeNotSupported
+			);
+		}
+
+		#[test]
+		fn votes_are_unweighted_by_default() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			test::set_block_timestamp::<Environment>(0);
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 2);
+			assert_eq!(voting.raw_votes_for(accounts.bob), 2);
+			voting.revoke_vote(accounts.alice, accounts.bob, 1).unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+			assert_eq!(voting.raw_votes_for(accounts.bob), 1);
+		}
+
+		#[test]
+		fn emergency_stop_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.bob, 5).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			voting.emergency_stop().unwrap();
+			assert!(voting.is_stopped());
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting
+					.vote_candidate(accounts.bob, accounts.alice, 1)
+					.unwrap_err(),
+				VotingError::ContractStopped
+			);
+			set_caller(accounts.alice);
+			assert_eq!(
+				voting.buy_ticket(accounts.charlie, 5).unwrap_err(),
+				VotingError::ContractStopped
+			);
+			assert_eq!(voting.unpause().unwrap_err(), VotingError::ContractStopped);
+			assert_eq!(
+				voting.emergency_stop().unwrap_err(),
+				VotingError::ContractStopped
+			);
+			// 投票人领回未投出的票
+			assert_eq!(voting.claim_emergency_refund(), 6);
+			assert_eq!(voting.claim_emergency_refund(), 0);
+			set_caller(accounts.bob);
+			assert_eq!(voting.claim_emergency_refund(), 5);
+			assert_eq!(
+				voting
+					.transfer_tickets(accounts.bob, accounts.alice, 1)
+					.unwrap_err(),
+				VotingError::ContractStopped
+			);
+		}
+
+		#[test]
+		fn payable_claim_emergency_refund_transfers_balance() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 3,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 30);
+			voting.buy_ticket(accounts.alice, 30).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			voting.emergency_stop().unwrap();
+			// 合约余额不足时转账失败, 待领的票保留
+			fund_contract(10);
+			assert_eq!(voting.claim_emergency_refund(), 0);
+			assert_eq!(account_balance(accounts.alice), before);
+			fund_contract(1000);
+			assert_eq!(voting.claim_emergency_refund(), 6);
+			assert_eq!(account_balance(accounts.alice), before + 18);
+			assert_eq!(voting.claim_emergency_refund(), 0);
+			assert_eq!(account_balance(accounts.alice), before + 18);
+		}
+
+		#[test]
+		fn emergency_stop_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.bob, 5).unwrap();
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.emergency_stop().unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert!(!voting.is_stopped());
+			assert_eq!(voting.claim_emergency_refund(), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 5);
+		}
+
+		#[test]
+		fn abstain_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 20,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting.abstain_vote(accounts.alice, 3).unwrap();
+			voting.abstain_vote(accounts.alice, 2).unwrap();
+			set_caller(accounts.charlie);
+			voting.abstain_vote(accounts.charlie, 1).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+			assert_eq!(voting.voter_abstentions(accounts.alice), 5);
+			assert_eq!(voting.voter_abstentions(accounts.charlie), 1);
+			assert_eq!(voting.total_abstentions(), 6);
+			assert_eq!(voting.total_votes_for(accounts.alice), 0);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			// 弃权票也算作参与投票
+			assert_eq!(voting.get_turnout_bps(), 3000);
+		}
+
+		#[test]
+		fn abstain_vote_without_enough_tickets_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 2).unwrap();
+			assert_eq!(
+				voting.abstain_vote(accounts.alice, 1).unwrap_err(),
+				VotingError::WrongPhase
+			);
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.abstain_vote(accounts.alice, 3).unwrap_err(),
+				VotingError::InsufficientTickets
+			);
+			assert_eq!(
+				voting.abstain_vote(accounts.alice, 0).unwrap_err(),
+				VotingError::ZeroAmount
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 2);
+			assert_eq!(voting.total_abstentions(), 0);
+		}
+
+		#[test]
+		fn get_candidate_rank_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![
+				accounts.alice,
+				accounts.bob,
+				accounts.charlie,
+				accounts.django
+			];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.django, 3)
+				.unwrap();
+			assert_eq!(voting.get_candidate_rank(accounts.bob), Some(1));
+			// 平票的候选人名次相同
+			assert_eq!(voting.get_candidate_rank(accounts.charlie), Some(2));
+			assert_eq!(voting.get_candidate_rank(accounts.django), Some(2));
+			assert_eq!(voting.get_candidate_rank(accounts.alice), Some(4));
+			assert_eq!(voting.get_candidate_rank(accounts.eve), None);
+		}
+
+		#[test]
+		fn get_candidate_rank_without_votes_is_first() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_candidate_rank(accounts.alice), Some(1));
+			assert_eq!(voting.get_candidate_rank(accounts.bob), Some(1));
+		}
+
+		#[test]
+		fn vote_candidate_emits_vote_cast() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			set_caller(accounts.charlie);
+			voting
+				.vote_candidate(accounts.charlie, accounts.bob, 3)
+				.unwrap();
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			let event = emitted_events.last().expect("no event emitted");
+			let decoded = <Event as scale::Decode>::decode(&mut &event.data[..])
+				.expect("encountered invalid contract event data buffer");
+			if let Event::VoteCast(VoteCast {
+				from,
+				to,
+				amount,
+				voter_remaining,
+				candidate_total,
+				block_number,
+				..
+			}) = decoded
+			{
+				assert_eq!(from, accounts.charlie);
+				assert_eq!(to, accounts.bob);
+				assert_eq!(amount, 3);
+				assert_eq!(voter_remaining, 7);
+				assert_eq!(candidate_total, 3);
+				assert_eq!(
+					block_number,
+					ink_env::block_number::<Environment>().unwrap()
+				);
+			} else {
+				panic!("encountered unexpected event kind: expected a VoteCast event")
+			}
+		}
+
+		#[test]
+		fn override_vote_count_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting.override_vote_count(accounts.bob, 7).unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 7);
+			assert_eq!(
+				voting.get_current_votes(),
+				ink_prelude::vec![
+					VoteOfCandidate {
+						candidate: accounts.alice,
+						vote: 0
+					},
+					VoteOfCandidate {
+						candidate: accounts.bob,
+						vote: 7
+					},
+				]
+			);
+
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			let event = emitted_events.last().expect("no event emitted");
+			let decoded = <Event as scale::Decode>::decode(&mut &event.data[..])
+				.expect("encountered invalid contract event data buffer");
+			if let Event::VoteCountOverridden(VoteCountOverridden {
+				candidate,
+				old_count,
+				new_count,
+				..
+			}) = decoded
+			{
+				assert_eq!(candidate, accounts.bob);
+				assert_eq!(old_count, 3);
+				assert_eq!(new_count, 7);
+			} else {
+				panic!("encountered unexpected event kind: expected a VoteCountOverridden event")
+			}
+		}
+
+		#[test]
+		fn override_vote_count_invalid_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.override_vote_count(accounts.charlie, 7).unwrap_err(),
+				VotingError::NotACandidate
+			);
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.override_vote_count(accounts.bob, 7).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+		}
+
+		#[test]
+		fn lock_candidate_list_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert!(!voting.is_candidate_list_locked());
+			voting.add_candidate(accounts.charlie).unwrap();
+			voting.lock_candidate_list().unwrap();
+			assert!(voting.is_candidate_list_locked());
+			assert_eq!(
+				voting.add_candidate(accounts.django).unwrap_err(),
+				VotingError::CandidateListLocked
+			);
+			assert_eq!(
+				voting.remove_candidate(accounts.bob).unwrap_err(),
+				VotingError::CandidateListLocked
+			);
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie]
+			);
+			// 锁定不能撤销, 再次锁定也会失败
+			assert_eq!(
+				voting.lock_candidate_list().unwrap_err(),
+				VotingError::CandidateListLocked
+			);
+			assert!(voting.is_candidate_list_locked());
+		}
+
+		#[test]
+		fn lock_candidate_list_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.lock_candidate_list().unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert!(!voting.is_candidate_list_locked());
+		}
+
+		#[test]
+		fn get_votes_cast_by_voter_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.alice, 5).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_votes_cast_by_voter(accounts.alice), 0);
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 4)
+				.unwrap();
+			assert_eq!(voting.get_votes_cast_by_voter(accounts.alice), 9);
+			assert_eq!(
+				voting.voter_ticket_balance(accounts.alice)
+					+ voting.get_votes_cast_by_voter(accounts.alice),
+				15
+			);
+			// 撤回的票回到余额中
+			voting.revoke_vote(accounts.alice, accounts.bob, 3).unwrap();
+			assert_eq!(voting.get_votes_cast_by_voter(accounts.alice), 6);
+			assert_eq!(
+				voting.voter_ticket_balance(accounts.alice)
+					+ voting.get_votes_cast_by_voter(accounts.alice),
+				15
+			);
+			assert_eq!(voting.get_votes_cast_by_voter(accounts.bob), 0);
+		}
+
+		#[test]
+		fn set_metadata_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					metadata: String::from("ipfs://first"),
+					..default_config()
+				},
+			);
+			assert_eq!(voting.get_metadata(), String::from("ipfs://first"));
+			let deployed_at = voting.metadata_updated_at();
+			test::advance_block::<Environment>().unwrap();
+			voting
+				.set_metadata(String::from("https://example.com/election"))
+				.unwrap();
+			assert_eq!(
+				voting.get_metadata(),
+				String::from("https://example.com/election")
+			);
+			assert_eq!(voting.metadata_updated_at(), deployed_at + 1);
+		}
+
+		#[test]
+		fn set_metadata_too_long_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			let metadata: String = core::iter::repeat('a').take(MAX_METADATA_LEN).collect();
+			voting.set_metadata(metadata.clone()).unwrap();
+			let too_long: String = core::iter::repeat('a').take(MAX_METADATA_LEN + 1).collect();
+			assert_eq!(
+				voting.set_metadata(too_long).unwrap_err(),
+				VotingError::MetadataTooLong
+			);
+			assert_eq!(voting.get_metadata(), metadata);
+		}
+
+		#[test]
+		fn set_metadata_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting
+					.set_metadata(String::from("ipfs://other"))
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_metadata(), String::new());
+		}
+
+		#[test]
+		fn token_weighted_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_contract: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			assert_eq!(voting.token_contract(), Some(accounts.frank));
+			set_mock_balance(accounts.frank, accounts.alice, 3);
+			set_mock_balance(accounts.frank, accounts.bob, 5);
+			voting.lock_tokens(3).unwrap();
+			set_caller(accounts.bob);
+			voting.lock_tokens(5).unwrap();
+			set_caller(accounts.alice);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.bob, accounts.charlie, 1)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.charlie), 11);
+			assert_eq!(voting.raw_votes_for(accounts.charlie), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 8);
+			// 锁定的代币已转入合约, 不能再转给别人重复投票
+			assert_eq!(mock_balance_of(accounts.frank, accounts.alice), 0);
+			assert_eq!(voting.locked_tokens_of(accounts.alice), 3);
+			assert_eq!(voting.unlock_tokens(), Err(VotingError::WrongPhase));
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.unlock_tokens(), Ok(3));
+			assert_eq!(mock_balance_of(accounts.frank, accounts.alice), 3);
+			assert_eq!(voting.unlock_tokens(), Err(VotingError::ZeroAmount));
+		}
+
+		#[test]
+		fn token_weight_is_scaled_and_capped() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_contract: Some(accounts.frank),
+					token_weight_divisor: 1_000,
+					max_token_weight: 4,
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, u128::MAX);
+			voting.lock_tokens(2_999).unwrap();
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 2);
+			// 锁定再多代币权重也不超过max_token_weight, 不会溢出
+			voting.lock_tokens(u128::MAX / 2).unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 6);
+		}
+
+		#[test]
+		fn token_weight_config_without_divisor_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			assert_eq!(
+				Voting::try_new(
+					candidates,
+					VotingConfig {
+						token_contract: Some(accounts.frank),
+						token_weight_divisor: 0,
+						..default_config()
+					},
+				)
+				.err(),
+				Some(VotingError::InvalidTokenWeight)
+			);
+		}
+
+		#[test]
+		fn lock_tokens_without_token_contract_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.lock_tokens(1), Err(VotingError::TokenWeightDisabled));
+		}
+
+		#[test]
+		fn token_weighted_vote_without_balance_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_contract: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+					.unwrap_err(),
+				VotingError::NoTokenBalance
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+		}
+
+		#[test]
+		fn token_weighted_revoke_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_contract: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 2);
+			voting.lock_tokens(2).unwrap();
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(
+				voting
+					.revoke_vote(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::RevokeNotSupported
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 4);
+		}
+
+		#[test]
+		fn get_election_summary_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			let summary = voting.get_election_summary();
+			assert_eq!(summary.total_tokens, voting.all_ticket_num());
+			assert_eq!(summary.balance_tokens, voting.left_ticket_num());
+			assert_eq!(summary.balance_tokens, 90);
+			assert_eq!(summary.candidate_count, voting.get_candidates_len());
+			assert_eq!(summary.votes, voting.get_current_votes());
+			// 投票结束前没有获胜者
+			assert_eq!(summary.winner, None);
+			voting.advance_phase().unwrap();
+			let summary = voting.get_election_summary();
+			assert_eq!(summary.winner, voting.get_winner().unwrap());
+			assert_eq!(summary.winner, Some(accounts.bob));
+		}
+
+		#[test]
+		fn export_state_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.bob, accounts.alice];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.add_candidate(accounts.charlie).unwrap();
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 3)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			let exported = voting.export_state();
+			assert_eq!(exported, voting.export_state());
+			let snapshot = <StateSnapshot as scale::Decode>::decode(&mut &exported[..])
+				.expect("encountered invalid state snapshot");
+			// 候选人按加入顺序排列
+			assert_eq!(
+				snapshot.candidates,
+				ink_prelude::vec![accounts.bob, accounts.alice, accounts.charlie]
+			);
+			assert_eq!(snapshot.votes, ink_prelude::vec![0, 3, 0]);
+			assert_eq!(snapshot.total_tokens, voting.all_ticket_num());
+			assert_eq!(snapshot.balance_tokens, voting.left_ticket_num());
+			assert_eq!(snapshot.balance_tokens, 90);
+			assert_eq!(snapshot.block_number, 1);
+		}
+
+		#[test]
+		fn import_state_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.bob, accounts.alice];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.add_candidate(accounts.charlie).unwrap();
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 4)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			let exported = voting.export_state();
+			let snapshot = <StateSnapshot as scale::Decode>::decode(&mut &exported[..])
+				.expect("encountered invalid state snapshot");
+
+			let mut restored = Voting::new(ink_prelude::vec![accounts.django], default_config());
+			restored.import_state(snapshot).unwrap();
+			assert_eq!(restored.export_state(), exported);
+			assert_eq!(restored.get_candidates(), voting.get_candidates());
+			assert_eq!(restored.get_current_votes(), voting.get_current_votes());
+			assert_eq!(restored.all_ticket_num(), voting.all_ticket_num());
+			assert_eq!(restored.left_ticket_num(), voting.left_ticket_num());
+			assert!(!restored.is_candidate(accounts.django));
+			assert_eq!(restored.verify_election_integrity(), Vec::new());
+
+			let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+			let event = emitted_events.last().expect("no event emitted");
+			let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+				.expect("encountered invalid contract event data buffer");
+			if let Event::StateImported(StateImported { block_number, .. }) = decoded_event {
+				assert_eq!(block_number, 0);
+			} else {
+				panic!("encountered unexpected event kind: expected a StateImported event")
+			}
+		}
+
+		#[test]
+		fn import_invalid_state_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			let snapshot =
+				|candidates: Vec<AccountId>, votes: Vec<u128>, balance_tokens| StateSnapshot {
+					candidates,
+					votes,
+					total_tokens: 100,
+					balance_tokens,
+					block_number: 0,
+				};
+			assert_eq!(
+				voting
+					.import_state(snapshot(
+						ink_prelude::vec![accounts.charlie],
+						Vec::new(),
+						100
+					))
+					.unwrap_err(),
+				VotingError::InvalidSnapshot
+			);
+			assert_eq!(
+				voting
+					.import_state(snapshot(
+						ink_prelude::vec![accounts.charlie, accounts.charlie],
+						ink_prelude::vec![1, 2],
+						100
+					))
+					.unwrap_err(),
+				VotingError::DuplicateCandidate
+			);
+			assert_eq!(
+				voting
+					.import_state(snapshot(
+						ink_prelude::vec![accounts.charlie],
+						ink_prelude::vec![1],
+						101
+					))
+					.unwrap_err(),
+				VotingError::TicketPoolExceedsTotal
+			);
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![accounts.alice, accounts.bob]
+			);
+			// 已经有人买票后不能导入
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			assert_eq!(
+				voting
+					.import_state(snapshot(
+						ink_prelude::vec![accounts.charlie],
+						ink_prelude::vec![1],
+						100
+					))
+					.unwrap_err(),
+				VotingError::VotesAlreadyCast
+			);
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting
+					.import_state(snapshot(
+						ink_prelude::vec![accounts.charlie],
+						ink_prelude::vec![1],
+						100
+					))
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+		}
+
+		#[test]
+		fn import_state_after_lock_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.lock_candidate_list().unwrap();
+			assert_eq!(
+				voting
+					.import_state(StateSnapshot {
+						candidates: ink_prelude::vec![accounts.charlie],
+						votes: ink_prelude::vec![1],
+						total_tokens: 100,
+						balance_tokens: 99,
+						block_number: 0,
+					})
+					.unwrap_err(),
+				VotingError::CandidateListLocked
+			);
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![accounts.alice, accounts.bob]
+			);
+		}
+
+		#[test]
+		fn import_state_over_max_supply_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_supply: 100,
+					..default_config()
+				},
+			);
+			assert_eq!(
+				voting
+					.import_state(StateSnapshot {
+						candidates: ink_prelude::vec![accounts.charlie],
+						votes: ink_prelude::vec![1],
+						total_tokens: 101,
+						balance_tokens: 100,
+						block_number: 0,
+					})
+					.unwrap_err(),
+				VotingError::MaxSupplyExceeded
+			);
+			assert_eq!(voting.all_ticket_num(), 100);
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![accounts.alice, accounts.bob]
+			);
+		}
+
+		#[test]
+		fn expire_votes_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					vote_expiry_blocks: 2,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			test::advance_block::<Environment>().unwrap();
+			// 刚好到期的区块还不能过期
+			assert_eq!(
+				voting
+					.expire_votes(accounts.alice, accounts.bob)
+					.unwrap_err(),
+				VotingError::VoteNotExpired
+			);
+			test::advance_block::<Environment>().unwrap();
+			set_caller(accounts.charlie);
+			voting.expire_votes(accounts.alice, accounts.bob).unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			assert_eq!(voting.raw_votes_for(accounts.bob), 0);
+			assert_eq!(voting.total_votes_for(accounts.alice), 1);
+			assert_eq!(
+				voting.get_voter_history(accounts.alice),
+				ink_prelude::vec![(accounts.alice, 1)]
+			);
+			// 过期的票不会退回
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 6);
+			assert_eq!(
+				voting
+					.expire_votes(accounts.alice, accounts.bob)
+					.unwrap_err(),
+				VotingError::InsufficientVotes
+			);
+		}
+
+		#[test]
+		fn expire_votes_refreshed_by_new_vote() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					vote_expiry_blocks: 1,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			assert_eq!(
+				voting
+					.expire_votes(accounts.alice, accounts.bob)
+					.unwrap_err(),
+				VotingError::VoteNotExpired
+			);
+			test::advance_block::<Environment>().unwrap();
+			voting.expire_votes(accounts.alice, accounts.bob).unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+		}
+
+		#[test]
+		fn expire_votes_disabled_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			for _ in 0..5 {
+				test::advance_block::<Environment>().unwrap();
+			}
+			assert_eq!(
+				voting
+					.expire_votes(accounts.alice, accounts.bob)
+					.unwrap_err(),
+				VotingError::VoteNotExpired
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+		}
+
+		#[test]
+		fn commit_reveal_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting
+				.commit_vote(accounts.alice, Voting::commitment_of(accounts.bob, 42))
+				.unwrap();
+			// 报名阶段不能揭示
+			assert_eq!(
+				voting
+					.reveal_vote(accounts.alice, accounts.bob, 42)
+					.unwrap_err(),
+				VotingError::WrongPhase
+			);
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			voting
+				.reveal_vote(accounts.alice, accounts.bob, 42)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 9);
+			// 不能重复揭示
+			assert_eq!(
+				voting
+					.reveal_vote(accounts.alice, accounts.bob, 42)
+					.unwrap_err(),
+				VotingError::NoCommitment
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+		}
+
+		#[test]
+		fn commit_reveal_vote_tampered_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting
+				.commit_vote(accounts.alice, Voting::commitment_of(accounts.bob, 42))
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.reveal_vote(accounts.alice, accounts.alice, 42)
+					.unwrap_err(),
+				VotingError::CommitmentMismatch
+			);
+			assert_eq!(
+				voting
+					.reveal_vote(accounts.alice, accounts.bob, 43)
+					.unwrap_err(),
+				VotingError::CommitmentMismatch
+			);
+			assert_eq!(voting.total_votes_for(accounts.alice), 0);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			// 承诺仍然有效, 之后可以正确揭示
+			voting
+				.reveal_vote(accounts.alice, accounts.bob, 42)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+		}
+
+		#[test]
+		fn commit_vote_invalid_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			let commitment = Voting::commitment_of(accounts.bob, 42);
+			assert_eq!(
+				voting.commit_vote(accounts.bob, commitment).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.commit_vote(accounts.alice, commitment).unwrap_err(),
+				VotingError::WrongPhase
+			);
+			assert_eq!(
+				voting
+					.reveal_vote(accounts.alice, accounts.bob, 42)
+					.unwrap_err(),
+				VotingError::NoCommitment
+			);
+		}
+
+		#[test]
+		fn get_voter_profile_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.get_voter_profile(accounts.alice),
+				VoterProfile {
+					ticket_balance: 0,
+					total_spent: 0,
+					delegated_to: None,
+					votes: Vec::new(),
+				}
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 2)
+				.unwrap();
+			voting.delegate_vote(accounts.alice, accounts.eve).unwrap();
+			assert_eq!(
+				voting.get_voter_profile(accounts.alice),
+				VoterProfile {
+					ticket_balance: 0,
+					total_spent: 5,
+					delegated_to: Some(accounts.eve),
+					votes: ink_prelude::vec![(accounts.bob, 3), (accounts.alice, 2)],
+				}
+			);
+			assert_eq!(
+				voting.get_voter_profile(accounts.eve),
+				VoterProfile {
+					ticket_balance: 5,
+					total_spent: 0,
+					delegated_to: None,
+					votes: Vec::new(),
+				}
+			);
+		}
+
+		#[test]
+		fn set_candidate_alias_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_candidate_alias(accounts.bob), None);
+			// 管理员可以给候选人设置别名
+			voting
+				.set_candidate_alias(accounts.bob, String::from("Bob"))
+				.unwrap();
+			// 候选人可以给自己设置别名, 允许重名
+			set_caller(accounts.charlie);
+			voting
+				.set_candidate_alias(accounts.charlie, String::from("Bob"))
+				.unwrap();
+			assert_eq!(
+				voting.get_candidate_alias(accounts.bob),
+				Some(String::from("Bob"))
+			);
+			assert_eq!(
+				voting.get_candidate_alias(accounts.charlie),
+				Some(String::from("Bob"))
+			);
+			let alias: String = core::iter::repeat('a').take(MAX_ALIAS_LEN).collect();
+			voting
+				.set_candidate_alias(accounts.charlie, alias.clone())
+				.unwrap();
+			assert_eq!(voting.get_candidate_alias(accounts.charlie), Some(alias));
+		}
+
+		#[test]
+		fn set_candidate_alias_invalid_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			let too_long: String = core::iter::repeat('a').take(MAX_ALIAS_LEN + 1).collect();
+			assert_eq!(
+				voting
+					.set_candidate_alias(accounts.bob, too_long)
+					.unwrap_err(),
+				VotingError::AliasTooLong
+			);
+			// 非候选人不能设置别名
+			set_caller(accounts.eve);
+			assert_eq!(
+				voting
+					.set_candidate_alias(accounts.eve, String::from("Eve"))
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+			// 不能给其他候选人设置别名
+			assert_eq!(
+				voting
+					.set_candidate_alias(accounts.bob, String::from("Eve"))
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_candidate_alias(accounts.bob), None);
+			assert_eq!(voting.get_candidate_alias(accounts.eve), None);
+		}
+
+		#[test]
+		fn init_invalid_config_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.alice];
+			assert_eq!(
+				Voting::try_new(candidates, default_config()).err(),
+				Some(VotingError::DuplicateCandidate)
+			);
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			assert_eq!(
+				Voting::try_new(
+					candidates.clone(),
+					VotingConfig {
+						token_price: 1,
+						min_price: 2,
+						..default_config()
+					}
+				)
+				.err(),
+				Some(VotingError::PriceBelowMinimum)
+			);
+			assert_eq!(
+				Voting::try_new(
+					candidates.clone(),
+					VotingConfig {
+						max_supply: 99,
+						..default_config()
+					}
+				)
+				.err(),
+				Some(VotingError::MaxSupplyExceeded)
+			);
+			assert!(Voting::try_new(candidates, default_config()).is_ok());
+		}
+
+		#[test]
+		fn get_candidates_paginated_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.get_candidates_paginated(0, 2),
+				ink_prelude::vec![accounts.alice, accounts.bob]
+			);
+			// limit超过剩余数量时返回剩余的全部
+			assert_eq!(
+				voting.get_candidates_paginated(1, 10),
+				ink_prelude::vec![accounts.bob, accounts.charlie]
+			);
+			assert_eq!(voting.get_candidates_paginated(0, 0), Vec::new());
+			assert_eq!(voting.get_candidates_paginated(3, 1), Vec::new());
+			assert_eq!(
+				voting.get_candidates_paginated(u32::MAX, u32::MAX),
+				Vec::new()
+			);
+		}
+
+		#[test]
+		fn get_votes_paginated_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 3)
+				.unwrap();
+			assert_eq!(
+				voting.get_votes_paginated(1, 10),
+				ink_prelude::vec![
+					VoteOfCandidate {
+						candidate: accounts.bob,
+						vote: 0
+					},
+					VoteOfCandidate {
+						candidate: accounts.charlie,
+						vote: 3
+					},
+				]
+			);
+			assert_eq!(voting.get_votes_paginated(0, 0), Vec::new());
+			assert_eq!(voting.get_votes_paginated(5, 2), Vec::new());
+		}
+
+		#[test]
+		fn get_participation_rate_bps_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_participation_rate_bps(), 0);
+			voting.register_voter(accounts.charlie).unwrap();
+			voting.register_voter(accounts.django).unwrap();
+			assert_eq!(voting.get_participation_rate_bps(), 0);
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			// 没有登记的用户买票不计入
+			voting.buy_ticket(accounts.eve, 10).unwrap();
+			assert_eq!(voting.get_participation_rate_bps(), 5_000);
+			voting.buy_ticket(accounts.django, 10).unwrap();
+			assert_eq!(voting.get_participation_rate_bps(), 10_000);
+			// 重复买票不会重复计算
+			voting.buy_ticket(accounts.django, 10).unwrap();
+			assert_eq!(voting.get_participation_rate_bps(), 10_000);
+		}
+
+		#[test]
+		fn register_voter_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.eve);
+			assert_eq!(
+				voting.register_voter(accounts.eve).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_participation_rate_bps(), 0);
+		}
+
+		#[test]
+		fn veto_candidate_skips_vetoed_winner() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					veto_threshold_bps: 2_000,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.bob, accounts.bob, 3)
+				.unwrap();
+			set_caller(accounts.bob);
+			voting.veto_candidate(accounts.alice).unwrap();
+			// 1 * 10000 <= 8 * 2000, 还没有被否决
+			assert_eq!(voting.vetoes_for(accounts.alice), 1);
+			assert!(!voting.is_vetoed(accounts.alice));
+			set_caller(accounts.charlie);
+			voting.veto_candidate(accounts.alice).unwrap();
+			assert_eq!(voting.vetoes_for(accounts.alice), 2);
+			assert!(voting.is_vetoed(accounts.alice));
+			set_caller(accounts.alice);
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_winner().unwrap(), Some(accounts.bob));
+			assert_eq!(
+				voting.get_winners().unwrap(),
+				ink_prelude::vec![accounts.bob]
+			);
+		}
+
+		#[test]
+		fn veto_candidate_invalid_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					veto_threshold_bps: 2_000,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			voting.advance_phase().unwrap();
+			// 没有买过票的用户不能否决
+			assert_eq!(
+				voting.veto_candidate(accounts.bob).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.veto_candidate(accounts.eve).unwrap_err(),
+				VotingError::NotACandidate
+			);
+			voting.veto_candidate(accounts.alice).unwrap();
+			assert_eq!(
+				voting.veto_candidate(accounts.alice).unwrap_err(),
+				VotingError::AlreadyVetoed
+			);
+			assert_eq!(voting.vetoes_for(accounts.alice), 1);
+		}
+
+		#[test]
+		fn quorum_met_returns_winner() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					quorum_votes: 10,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 9)
+				.unwrap();
+			assert!(!voting.is_quorum_met());
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert!(voting.is_quorum_met());
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_winner().unwrap(), Some(accounts.bob));
+		}
+
+		#[test]
+		fn quorum_not_met_has_no_winner() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					quorum_votes: 10,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 9)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert!(!voting.is_quorum_met());
+			assert_eq!(voting.get_winner().unwrap(), None);
+			assert_eq!(voting.get_winners().unwrap(), Vec::new());
+		}
+
+		#[test]
+		fn clone_params_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates.clone(),
+				VotingConfig {
+					token_price: 2,
+					..default_config()
+				},
+			);
+			voting.add_candidate(accounts.charlie).unwrap();
+			assert_eq!(
+				voting.clone_params(),
+				(
+					ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie],
+					100,
+					2
+				)
+			);
+		}
+
+		#[test]
+		fn clone_from_is_independent() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut source = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					mode: VotingMode::Quadratic,
+					..default_config()
+				},
+			);
+			source.buy_ticket(accounts.alice, 20).unwrap();
+			source.advance_phase().unwrap();
+			source
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			let mut cloned = Voting::clone_from(&source);
+			assert_eq!(cloned.get_candidates(), source.get_candidates());
+			assert_eq!(cloned.all_ticket_num(), 100);
+			assert_eq!(cloned.left_ticket_num(), 100);
+			assert_eq!(cloned.voting_mode(), VotingMode::Quadratic);
+			assert_eq!(cloned.total_votes_for(accounts.bob), 0);
+			assert_eq!(cloned.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(cloned.current_phase(), VotingPhase::Registration);
+			cloned.buy_ticket(accounts.alice, 4).unwrap();
+			assert_eq!(cloned.voter_ticket_balance(accounts.alice), 2);
+			assert_eq!(source.voter_ticket_balance(accounts.alice), 1);
+			assert_eq!(source.total_votes_for(accounts.bob), 3);
+		}
+
+		#[test]
+		fn log2_fixed_works() {
+			assert_eq!(Voting::log2_fixed(1), 0);
+			assert_eq!(Voting::log2_fixed(2), 1 << LOG2_FRACTION_BITS);
+			assert_eq!(Voting::log2_fixed(1 << 100), 100 << LOG2_FRACTION_BITS);
+			// log2(3) = 1.5849625...
+			let log2_3 = Voting::log2_fixed(3);
+			assert_eq!((log2_3 * 10_000_000) >> LOG2_FRACTION_BITS, 15_849_625);
+		}
+
+		#[test]
+		fn get_vote_distribution_entropy_bps_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![
+				accounts.alice,
+				accounts.bob,
+				accounts.charlie,
+				accounts.django
+			];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_vote_distribution_entropy_bps(), 0);
+			voting.buy_ticket(accounts.alice, 50).unwrap();
+			voting.advance_phase().unwrap();
+			// 全部集中在一个候选人时熵最小
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			assert_eq!(voting.get_vote_distribution_entropy_bps(), 0);
+			// 平均分布时熵最大
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 4)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 4)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.django, 4)
+				.unwrap();
+			assert_eq!(voting.get_vote_distribution_entropy_bps(), 10_000);
+		}
+
+		#[test]
+		fn get_vote_distribution_entropy_bps_uneven() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 50).unwrap();
+			voting.advance_phase().unwrap();
+			for candidate in [accounts.alice, accounts.bob, accounts.charlie].iter() {
+				voting
+					.vote_candidate_without_event(accounts.alice, *candidate, 3)
+					.unwrap();
+			}
+			// 3个候选人的log2(3)不是整数, 允许有舍入误差
+			assert!(voting.get_vote_distribution_entropy_bps() >= 9_999);
+			// 票数为3:1:0时 H = 0.8113 / log2(3) = 0.5119
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 50).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			let entropy = voting.get_vote_distribution_entropy_bps();
+			assert!((5_118..=5_119).contains(&entropy));
+		}
+
+		#[test]
+		fn vote_candidate_if_leading_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			voting.advance_phase().unwrap();
+			// 平票时preferred也算领先
+			assert_eq!(
+				voting.vote_candidate_if_leading(accounts.alice, accounts.bob, accounts.charlie, 2),
+				Ok(accounts.bob)
+			);
+			assert_eq!(
+				voting.vote_candidate_if_leading(accounts.alice, accounts.bob, accounts.charlie, 1),
+				Ok(accounts.bob)
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			// alice落后于bob, 投给fallback
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.vote_candidate_if_leading(accounts.bob, accounts.alice, accounts.charlie, 2),
+				Ok(accounts.charlie)
+			);
+			assert_eq!(voting.total_votes_for(accounts.alice), 0);
+			assert_eq!(voting.total_votes_for(accounts.charlie), 2);
+
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			let event = emitted_events.last().expect("no event emitted");
+			let decoded = <Event as scale::Decode>::decode(&mut &event.data[..])
+				.expect("encountered invalid contract event data buffer");
+			if let Event::VoteCast(VoteCast {
+				from, to, amount, ..
+			}) = decoded
+			{
+				assert_eq!(from, accounts.bob);
+				assert_eq!(to, accounts.charlie);
+				assert_eq!(amount, 2);
+			} else {
+				panic!("encountered unexpected event kind: expected a VoteCast event")
+			}
+		}
+
+		#[test]
+		fn vote_candidate_if_leading_invalid_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			// preferred领先但fallback无效时也不能投票
+			assert_eq!(
+				voting.vote_candidate_if_leading(accounts.alice, accounts.bob, accounts.eve, 2),
+				Err(VotingError::NotACandidate)
+			);
+			assert_eq!(
+				voting.vote_candidate_if_leading(accounts.alice, accounts.eve, accounts.bob, 2),
+				Err(VotingError::NotACandidate)
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+		}
+
+		#[test]
+		fn get_candidates_by_vote_range_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates.clone(), default_config());
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 9)
+				.unwrap();
+			assert_eq!(voting.get_candidates_by_vote_range(10, 20), Vec::new());
+			assert_eq!(voting.get_candidates_by_vote_range(0, 9), candidates);
+			assert_eq!(
+				voting.get_candidates_by_vote_range(3, 9),
+				ink_prelude::vec![accounts.bob, accounts.charlie]
+			);
+			assert_eq!(
+				voting.get_candidates_by_vote_range(5, 5),
+				ink_prelude::vec![accounts.bob]
+			);
+			assert_eq!(voting.get_candidates_by_vote_range(9, 2), Vec::new());
+		}
+
+		#[test]
+		fn get_max_achievable_votes_for_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_max_achievable_votes_for(accounts.bob), 100);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			assert_eq!(voting.get_max_achievable_votes_for(accounts.bob), 90);
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			assert_eq!(voting.get_max_achievable_votes_for(accounts.bob), 94);
+			assert_eq!(voting.get_max_achievable_votes_for(accounts.alice), 90);
+			assert_eq!(voting.get_max_achievable_votes_for(accounts.eve), 0);
+		}
+
+		#[test]
+		fn suggest_allocation_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.buy_ticket(accounts.django, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.django, accounts.bob, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.django, accounts.charlie, 5)
+				.unwrap();
+			// bob和charlie并列第一, 选列表中靠前的bob
+			assert_eq!(
+				voting.suggest_allocation(accounts.alice, AllocationStrategy::MaximizeLeader),
+				ink_prelude::vec![(accounts.bob, 20)]
+			);
+			assert_eq!(
+				voting.suggest_allocation(accounts.alice, AllocationStrategy::BoostUnderdog),
+				ink_prelude::vec![(accounts.alice, 20)]
+			);
+			assert_eq!(
+				voting.suggest_allocation(accounts.alice, AllocationStrategy::Spread),
+				ink_prelude::vec![
+					(accounts.alice, 7),
+					(accounts.bob, 7),
+					(accounts.charlie, 6)
+				]
+			);
+			for strategy in [
+				AllocationStrategy::MaximizeLeader,
+				AllocationStrategy::BoostUnderdog,
+				AllocationStrategy::Spread,
+			]
+			.iter()
+			{
+				let allocation = voting.suggest_allocation(accounts.alice, *strategy);
+				let total = allocation.iter().map(|(_, amount)| *amount).sum::<u128>();
+				assert_eq!(total, voting.voter_ticket_balance(accounts.alice));
+				assert_eq!(
+					voting.suggest_allocation(accounts.alice, *strategy),
+					allocation
+				);
+			}
+			// 票数少于候选人数时不返回0票的候选人
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 19)
+				.unwrap();
+			assert_eq!(
+				voting.suggest_allocation(accounts.alice, AllocationStrategy::Spread),
+				ink_prelude::vec![(accounts.alice, 1)]
+			);
+			// 没有票时返回空列表
+			assert_eq!(
+				voting.suggest_allocation(accounts.django, AllocationStrategy::Spread),
+				Vec::new()
+			);
+		}
+
+		#[test]
+		fn verify_election_integrity_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 3)
+				.unwrap();
+			voting.abstain_vote(accounts.alice, 2).unwrap();
+			assert_eq!(voting.verify_election_integrity(), Vec::new());
+			// 撤回的票回到投票人手中, 移除候选人后已投的票仍算作已花费
+			voting.revoke_vote(accounts.alice, accounts.bob, 2).unwrap();
+			assert_eq!(voting.verify_election_integrity(), Vec::new());
+			voting.remove_candidate(accounts.charlie).unwrap();
+			assert_eq!(voting.verify_election_integrity(), Vec::new());
+			voting.emergency_stop().unwrap();
+			assert_eq!(voting.verify_election_integrity(), Vec::new());
+			assert_eq!(voting.claim_emergency_refund(), 12);
+			assert_eq!(voting.verify_election_integrity(), Vec::new());
+		}
+
+		#[test]
+		fn verify_election_integrity_detects_corruption() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.in_candidate_list.insert(accounts.eve, ());
+			assert_eq!(
+				voting.verify_election_integrity(),
+				ink_prelude::vec![VotingError::CandidateIndexMismatch]
+			);
+			voting.in_candidate_list.take(&accounts.eve);
+			voting.balance_tokens = 101;
+			assert_eq!(
+				voting.verify_election_integrity(),
+				ink_prelude::vec![
+					VotingError::TicketPoolExceedsTotal,
+					VotingError::TicketSupplyMismatch
+				]
+			);
+			voting.balance_tokens = 80;
+			voting.voter_balance.insert(accounts.bob, 1);
+			assert_eq!(
+				voting.verify_election_integrity(),
+				ink_prelude::vec![VotingError::TicketSupplyMismatch]
+			);
+			voting.voter_balance.take(&accounts.bob);
+			// 少了票同样是不一致
+			voting.voter_balance.insert(accounts.alice, 15);
+			assert_eq!(
+				voting.verify_election_integrity(),
+				ink_prelude::vec![VotingError::TicketSupplyMismatch]
+			);
+			voting.voter_balance.insert(accounts.alice, 20);
+			voting.vote_num.insert((accounts.alice, accounts.eve), 1);
+			assert_eq!(
+				voting.verify_election_integrity(),
+				ink_prelude::vec![VotingError::OrphanedVote]
+			);
+		}
+
+		#[test]
+		fn get_effective_votes_for_decays_over_blocks() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					decay_rate_bps_per_block: 1000,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 100).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 50)
+				.unwrap();
+			assert_eq!(voting.get_effective_votes_for(accounts.bob), 50);
+			let mut effective = Vec::new();
+			for _ in 0..3 {
+				test::advance_block::<Environment>().unwrap();
+				effective.push(voting.get_effective_votes_for(accounts.bob));
+			}
+			// 每个区块衰减10%: 50 * 0.9, 50 * 0.81, 50 * 0.729
+			assert_eq!(effective, ink_prelude::vec![45, 40, 36]);
+			// votes_received仍是未衰减的票数
+			assert_eq!(voting.total_votes_for(accounts.bob), 50);
+			assert_eq!(voting.get_effective_votes_for(accounts.alice), 0);
+		}
+
+		#[test]
+		fn get_effective_votes_for_without_decay() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 7)
+				.unwrap();
+			for _ in 0..5 {
+				test::advance_block::<Environment>().unwrap();
+			}
+			assert_eq!(voting.get_effective_votes_for(accounts.bob), 7);
+		}
+
+		#[test]
+		fn vote_fee_goes_to_treasury() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					fee_bps: 1000,
+					fee_treasury: accounts.frank,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 50).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 20)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 18);
+			assert_eq!(voting.get_treasury_balance(), 2);
+			// 手续费向下取整, 9 * 10% 不足1票时不收取
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 9)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 27);
+			assert_eq!(voting.get_treasury_balance(), 2);
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 15)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.alice), 14);
+			assert_eq!(voting.get_treasury_balance(), 3);
+			// 投票人按投出的票数扣票, 不受手续费影响
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 6);
+			assert_eq!(
+				voting
+					.revoke_vote(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::RevokeNotSupported
+			);
+		}
+
+		#[test]
+		fn invalid_fee_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			assert_eq!(
+				Voting::try_new(
+					candidates,
+					VotingConfig {
+						fee_bps: 10_001,
+						..default_config()
+					},
+				)
+				.err(),
+				Some(VotingError::InvalidFee)
+			);
+		}
+
+		#[test]
+		fn invalid_vote_share_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			assert_eq!(
+				Voting::try_new(
+					candidates,
+					VotingConfig {
+						max_votes_share_bps: 10_001,
+						..default_config()
+					},
+				)
+				.err(),
+				Some(VotingError::InvalidVoteShare)
+			);
+		}
+
+		#[test]
+		fn max_votes_share_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: 200,
+					max_votes_share_bps: 5_000,
+					min_votes_for_share_cap: 100,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 61).unwrap();
+			voting.buy_ticket(accounts.bob, 40).unwrap();
+			voting.advance_phase().unwrap();
+			// 总票数达到min_votes_for_share_cap之前不检查
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 50)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.bob, accounts.bob, 40)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 10)
+				.unwrap();
+			// 第51票会让alice的占比超过50%
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+					.unwrap_err(),
+				VotingError::VoteShareExceeded
+			);
+			assert_eq!(voting.total_votes_for(accounts.alice), 50);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 1);
+		}
+
+		#[test]
+		fn vote_exceeding_share_is_rejected_entirely() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_share_bps: 5_000,
+					min_votes_for_share_cap: 9,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			// 投2票时第1票不会超过上限, 但整笔投票都不生效
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+					.unwrap_err(),
+				VotingError::VoteShareExceeded
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 4);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 4);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 11);
+			assert_eq!(voting.get_voter_receipts(accounts.alice).len(), 2);
+			// bob得5票时占比正好50%, 没有超过上限
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+		}
+
+		#[test]
+		fn transfer_and_override_respect_vote_share() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_share_bps: 5_000,
+					min_votes_for_share_cap: 9,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			// 转移后bob占5/9, 超过上限
+			assert_eq!(
+				voting
+					.transfer_votes(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::VoteShareExceeded
+			);
+			assert_eq!(voting.total_votes_for(accounts.alice), 5);
+			assert_eq!(voting.total_votes_for(accounts.bob), 4);
+			assert_eq!(
+				voting.override_vote_count(accounts.bob, 6).unwrap_err(),
+				VotingError::VoteShareExceeded
+			);
+			// 修改后bob占5/10, 未加权票数同步修改
+			voting.override_vote_count(accounts.bob, 5).unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+			assert_eq!(voting.raw_votes_for(accounts.bob), 5);
+			voting.override_vote_count(accounts.charlie, 1).unwrap();
+			assert_eq!(voting.raw_votes_for(accounts.charlie), 1);
+		}
+
+		#[test]
+		fn max_votes_share_without_quorum_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_share_bps: 5_000,
+					min_votes_for_share_cap: 10,
+					quorum_votes: 0,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 6)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			assert_eq!(voting.total_votes_cast, 10);
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+					.unwrap_err(),
+				VotingError::VoteShareExceeded
+			);
+			// 撤回后总票数低于min_votes_for_share_cap, 不再检查
+			voting.revoke_vote(accounts.alice, accounts.bob, 4).unwrap();
+			assert_eq!(voting.total_votes_cast, 6);
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+				.unwrap();
+			assert_eq!(voting.total_votes_cast, 7);
+			voting.reset_election().unwrap();
+			assert_eq!(voting.total_votes_cast, 0);
+		}
+
+		#[test]
+		fn max_votes_share_of_full_share_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_share_bps: 10_000,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			// 占比上限为100%时任何投票都不会超过上限
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 10)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.alice), 10);
+			assert_eq!(voting.total_votes_cast, 10);
+		}
+
+		#[test]
+		fn get_largest_gap_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_largest_gap(), Some(0));
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 7)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 2)
+				.unwrap();
+			assert_eq!(voting.get_largest_gap(), Some(5));
+			// 并列第一时差为0
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 7)
+				.unwrap();
+			assert_eq!(voting.get_largest_gap(), Some(0));
+		}
+
+		#[test]
+		fn get_largest_gap_with_single_candidate_is_none() {
+			let accounts = default_accounts();
+			let mut voting = Voting::new(ink_prelude::vec![accounts.alice], default_config());
+			assert_eq!(voting.get_largest_gap(), None);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 3)
+				.unwrap();
+			assert_eq!(voting.get_largest_gap(), None);
+		}
+
+		#[test]
+		fn get_confidence_margin_bps_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_confidence_margin_bps(), 0);
+			voting.buy_ticket(accounts.alice, 30).unwrap();
+			voting.advance_phase().unwrap();
+			// 只有1票时领先幅度为100%
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 1)
+				.unwrap();
+			assert_eq!(voting.get_confidence_margin_bps(), 10_000);
+			// (7 - 2) * 10000 / 9 向下取整
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 6)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 2)
+				.unwrap();
+			assert_eq!(voting.get_confidence_margin_bps(), 5555);
+			// 票数平均分布时为0
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 5)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 7)
+				.unwrap();
+			assert_eq!(voting.get_confidence_margin_bps(), 0);
+		}
+
+		#[test]
+		fn vote_as_proxy_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting.grant_proxy_allowance(accounts.charlie, 5).unwrap();
+			set_caller(accounts.charlie);
+			voting
+				.vote_as_proxy(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 7);
+			assert_eq!(
+				voting.get_proxy_allowance(accounts.alice, accounts.charlie),
+				2
+			);
+			// 额度用完后不能再代理投票
+			assert_eq!(
+				voting
+					.vote_as_proxy(accounts.alice, accounts.bob, 3)
+					.unwrap_err(),
+				VotingError::InsufficientAllowance
+			);
+			voting
+				.vote_as_proxy(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(
+				voting.get_proxy_allowance(accounts.alice, accounts.charlie),
+				0
+			);
+			assert_eq!(
+				voting
+					.vote_as_proxy(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::InsufficientAllowance
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+		}
+
+		#[test]
+		fn vote_for_other_owner_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			voting.advance_phase().unwrap();
+			// alice没有得到授权, 不能花bob的票
+			assert_eq!(
+				voting.vote_candidate(accounts.bob, accounts.alice, 3),
+				Err(VotingError::Unauthorized)
+			);
+			assert_eq!(
+				voting.vote_split(accounts.bob, ink_prelude::vec![(accounts.alice, 1)]),
+				Err(VotingError::Unauthorized)
+			);
+			assert_eq!(
+				voting.abstain_vote(accounts.bob, 1),
+				Err(VotingError::Unauthorized)
+			);
+			assert_eq!(
+				voting.batch_vote(accounts.bob, ink_prelude::vec![(accounts.alice, 1)]),
+				ink_prelude::vec![Err(VotingError::Unauthorized)]
+			);
+			assert_eq!(
+				voting.ranked_choice_vote(accounts.bob, ink_prelude::vec![accounts.alice]),
+				Err(VotingError::Unauthorized)
+			);
+			assert!(!VoteProxy::proxy_vote(
+				&mut voting,
+				accounts.bob,
+				accounts.alice,
+				1
+			));
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 10);
+			assert_eq!(voting.total_votes_for(accounts.alice), 0);
+		}
+
+		#[test]
+		fn vote_as_proxy_without_allowance_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting.grant_proxy_allowance(accounts.charlie, 5).unwrap();
+			set_caller(accounts.django);
+			assert_eq!(
+				voting
+					.vote_as_proxy(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::InsufficientAllowance
+			);
+			// 投票失败时不扣除额度
+			set_caller(accounts.charlie);
+			assert_eq!(
+				voting
+					.vote_as_proxy(accounts.alice, accounts.eve, 1)
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert_eq!(
+				voting.get_proxy_allowance(accounts.alice, accounts.charlie),
+				5
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+		}
+
+		#[test]
+		fn get_votes_since_block_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_history_blocks: 10,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			assert_eq!(
+				voting.get_votes_since_block(0),
+				ink_prelude::vec![
+					(1, accounts.alice, accounts.alice, 2),
+					(1, accounts.alice, accounts.bob, 3),
+					(2, accounts.alice, accounts.bob, 4),
+				]
+			);
+			assert_eq!(
+				voting.get_votes_since_block(1),
+				ink_prelude::vec![(2, accounts.alice, accounts.bob, 4)]
+			);
+			assert_eq!(voting.get_votes_since_block(2), Vec::new());
+		}
+
+		#[test]
+		fn get_candidate_velocity_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_history_blocks: 100,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 50).unwrap();
+			voting.advance_phase().unwrap();
+			// 在区块1到5各给bob投1到5票, 区块3还给alice投10票
+			for amount in 1..=5 {
+				test::advance_block::<Environment>().unwrap();
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, amount)
+					.unwrap();
+				if amount == 3 {
+					voting
+						.vote_candidate_without_event(accounts.alice, accounts.alice, 10)
+						.unwrap();
+				}
+			}
+			// 当前区块为5, 窗口为[3, 5]
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 2), 12);
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 0), 5);
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 100), 15);
+			assert_eq!(voting.get_candidate_velocity(accounts.alice, 1), 0);
+			assert_eq!(voting.get_candidate_velocity(accounts.alice, 2), 10);
+			// 之后没有新的投票, 速度下降
+			for _ in 0..3 {
+				test::advance_block::<Environment>().unwrap();
+			}
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 2), 0);
+		}
+
+		#[test]
+		fn get_candidate_velocity_drops_old_blocks() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_history_blocks: 2,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 2)
+				.unwrap();
+			assert_eq!(voting.vote_block_log.len(), 2);
+			test::advance_block::<Environment>().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 4)
+				.unwrap();
+			// 区块0的记录超出保留的2个区块, 投票时被删除
+			assert_eq!(voting.vote_block_log.len(), 2);
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 100), 0);
+			assert_eq!(voting.get_candidate_velocity(accounts.alice, 100), 6);
+		}
+
+		#[test]
+		fn get_candidate_velocity_with_default_config_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			// 不开启投票历史记录时也能统计得票速度
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			assert_eq!(voting.get_votes_since_block(0), Vec::new());
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 0), 6);
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 1), 9);
+			assert_eq!(voting.get_candidate_velocity(accounts.alice, 1), 0);
+			test::advance_block::<Environment>().unwrap();
+			test::advance_block::<Environment>().unwrap();
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 1), 0);
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 3), 9);
+		}
+
+		#[test]
+		fn vote_receipts_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			set_caller(accounts.bob);
+			voting
+				.vote_candidate(accounts.bob, accounts.alice, 2)
+				.unwrap();
+			set_caller(accounts.alice);
+			voting
+				.vote_candidate(accounts.alice, accounts.alice, 1)
+				.unwrap();
+			// 投票失败时不生成回执
+			assert!(voting
+				.vote_candidate(accounts.alice, accounts.eve, 1)
+				.is_err());
+			assert_eq!(
+				voting.get_voter_receipts(accounts.alice),
+				ink_prelude::vec![0, 2]
+			);
+			assert_eq!(
+				voting.get_voter_receipts(accounts.bob),
+				ink_prelude::vec![1]
+			);
+			assert_eq!(voting.get_voter_receipts(accounts.charlie), Vec::new());
+			assert_eq!(
+				voting.get_receipt(0),
+				Some(ReceiptDetails {
+					voter: accounts.alice,
+					candidate: accounts.bob,
+					amount: 3,
+					block: 0,
+				})
+			);
+			assert_eq!(
+				voting.get_receipt(2),
+				Some(ReceiptDetails {
+					voter: accounts.alice,
+					candidate: accounts.alice,
+					amount: 1,
+					block: 1,
+				})
+			);
+			assert_eq!(voting.get_receipt(3), None);
+		}
+
+		#[test]
+		fn get_votes_since_block_drops_old_blocks() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_history_blocks: 2,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			for amount in 1..=4 {
+				test::advance_block::<Environment>().unwrap();
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, amount)
+					.unwrap();
+			}
+			// 只保留最近2个区块的记录
+			assert_eq!(
+				voting.get_votes_since_block(0),
+				ink_prelude::vec![
+					(3, accounts.alice, accounts.bob, 3),
+					(4, accounts.alice, accounts.bob, 4),
+				]
+			);
+		}
+
+		#[test]
+		fn transfer_votes_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.django, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.django, accounts.bob, 8)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.django, accounts.charlie, 2)
+				.unwrap();
+			let total = voting.total_candidate_votes();
+			set_caller(accounts.bob);
+			voting
+				.transfer_votes(accounts.bob, accounts.charlie, 5)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.total_votes_for(accounts.charlie), 7);
+			// 总票数, 投票人的票数和投票记录都不变
+			assert_eq!(voting.total_candidate_votes(), total);
+			assert_eq!(voting.voter_ticket_balance(accounts.django), 10);
+			assert_eq!(voting.callee_vote_of(accounts.django, accounts.bob), 8);
+			assert_eq!(voting.left_ticket_num(), 80);
+			assert_eq!(
+				voting
+					.transfer_votes(accounts.bob, accounts.charlie, 4)
+					.unwrap_err(),
+				VotingError::InsufficientVotes
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+		}
+
+		#[test]
+		fn transfer_votes_by_other_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.django, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.django, accounts.bob, 8)
+				.unwrap();
+			assert_eq!(
+				voting
+					.transfer_votes(accounts.bob, accounts.charlie, 5)
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting
+					.transfer_votes(accounts.bob, accounts.eve, 5)
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 8);
+			assert_eq!(voting.total_votes_for(accounts.charlie), 0);
+		}
+
+		#[test]
+		fn get_gini_coefficient_bps_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![
+				accounts.alice,
+				accounts.bob,
+				accounts.charlie,
+				accounts.django
+			];
+			let mut voting = Voting::new(candidates.clone(), default_config());
+			assert_eq!(voting.get_gini_coefficient_bps(), 0);
+			voting.buy_ticket(accounts.alice, 40).unwrap();
+			voting.advance_phase().unwrap();
+			// 全部票数集中在一个候选人时为10000 * (n - 1) / n
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 8)
+				.unwrap();
+			assert_eq!(voting.get_gini_coefficient_bps(), 7500);
+			// 票数平均分布时为0
+			for candidate in candidates.iter().filter(|x| **x != accounts.bob) {
+				voting
+					.vote_candidate_without_event(accounts.alice, *candidate, 8)
+					.unwrap();
+			}
+			assert_eq!(voting.get_gini_coefficient_bps(), 0);
+		}
+
+		#[test]
+		fn get_gini_coefficient_bps_of_large_votes() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.votes_received.insert(accounts.alice, u128::MAX / 2);
+			assert_eq!(voting.get_gini_coefficient_bps(), 5000);
+			voting.votes_received.insert(accounts.bob, u128::MAX / 2);
+			assert_eq!(voting.get_gini_coefficient_bps(), 0);
+		}
+
+		#[test]
+		fn apply_conviction_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					conviction_period: 10,
+					max_conviction_multiplier: 3,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			// 持有不足一个周期时倍数为1
+			assert_eq!(voting.apply_conviction(accounts.alice, accounts.bob), Ok(4));
+			assert_eq!(voting.total_votes_for(accounts.bob), 4);
+			for _ in 0..20 {
+				test::advance_block::<Environment>().unwrap();
+			}
+			assert_eq!(voting.apply_conviction(accounts.alice, accounts.bob), Ok(8));
+			assert_eq!(voting.total_votes_for(accounts.bob), 8);
+			// 重复调用不会重复计入
+			assert_eq!(voting.apply_conviction(accounts.alice, accounts.bob), Ok(8));
+			assert_eq!(voting.total_votes_for(accounts.bob), 8);
+			// 倍数不超过max_conviction_multiplier
+			for _ in 0..30 {
+				test::advance_block::<Environment>().unwrap();
+			}
+			assert_eq!(
+				voting.apply_conviction(accounts.alice, accounts.bob),
+				Ok(12)
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 12);
+			// 再次投票重新计时
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			assert_eq!(voting.apply_conviction(accounts.alice, accounts.bob), Ok(5));
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+		}
+
+		#[test]
+		fn apply_conviction_when_disabled_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			assert_eq!(
+				voting
+					.apply_conviction(accounts.alice, accounts.bob)
+					.unwrap_err(),
+				VotingError::ConvictionDisabled
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 4);
+		}
+
+		#[test]
+		fn vote_split_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_split(
+					accounts.alice,
+					ink_prelude::vec![
+						(accounts.alice, 1),
+						(accounts.bob, 1),
+						(accounts.charlie, 1)
+					],
+				)
+				.unwrap();
+			// 10 / 3 向下取整为3, 余下的1张给第一个候选人
+			assert_eq!(voting.total_votes_for(accounts.alice), 4);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.total_votes_for(accounts.charlie), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.get_votes_cast_by_voter(accounts.alice), 10);
+			// 每个候选人各触发一次投票事件
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			let cast: Vec<_> = emitted_events[emitted_events.len() - 3..]
+				.iter()
+				.map(|event| {
+					let decoded = <Event as scale::Decode>::decode(&mut &event.data[..])
+						.expect("encountered invalid contract event data buffer");
+					if let Event::VoteCast(VoteCast {
+						to,
+						amount,
+						voter_remaining,
+						..
+					}) = decoded
+					{
+						(to, amount, voter_remaining)
+					} else {
+						panic!("encountered unexpected event kind: expected a VoteCast event")
+					}
+				})
+				.collect();
+			assert_eq!(
+				cast,
+				ink_prelude::vec![
+					(accounts.alice, 4, 6),
+					(accounts.bob, 3, 3),
+					(accounts.charlie, 3, 0)
+				]
+			);
+		}
+
+		#[test]
+		fn vote_split_skips_zero_allocations() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 7).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_split(
+					accounts.alice,
+					ink_prelude::vec![
+						(accounts.bob, 2),
+						(accounts.alice, 0),
+						(accounts.charlie, 5)
+					],
+				)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 2);
+			assert_eq!(voting.total_votes_for(accounts.alice), 0);
+			assert_eq!(voting.total_votes_for(accounts.charlie), 5);
+			assert_eq!(
+				voting.get_voter_history(accounts.alice),
+				ink_prelude::vec![(accounts.bob, 2), (accounts.charlie, 5)]
+			);
+		}
+
+		#[test]
+		fn vote_split_invalid_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_votes_per_candidate: 5,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 11).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_split(
+						accounts.alice,
+						ink_prelude::vec![(accounts.alice, 1), (accounts.eve, 1)]
+					)
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert_eq!(
+				voting
+					.vote_split(
+						accounts.alice,
+						ink_prelude::vec![(accounts.alice, 1), (accounts.alice, 1)]
+					)
+					.unwrap_err(),
+				VotingError::InvalidBallot
+			);
+			assert_eq!(
+				voting
+					.vote_split(accounts.alice, ink_prelude::vec![(accounts.alice, 0)])
+					.unwrap_err(),
+				VotingError::InvalidBallot
+			);
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting
+					.vote_split(accounts.bob, ink_prelude::vec![(accounts.alice, 1)])
+					.unwrap_err(),
+				VotingError::InsufficientTickets
+			);
+			set_caller(accounts.alice);
+			// bob分到5张, alice分到6张超过上限, 整笔都不生效
+			assert_eq!(
+				voting
+					.vote_split(
+						accounts.alice,
+						ink_prelude::vec![(accounts.bob, 5), (accounts.alice, 6)]
+					)
+					.unwrap_err(),
+				VotingError::CandidateCapExceeded
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 11);
+		}
+
+		#[test]
+		fn vote_split_quadratic_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					mode: VotingMode::Quadratic,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_split(accounts.alice, ink_prelude::vec![(accounts.bob, 1)])
+					.unwrap_err(),
+				VotingError::InvalidBallot
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+		}
+
+		#[test]
+		fn get_candidate_at_index_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			let voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_candidate_at_index(0), Some(accounts.alice));
+			assert_eq!(voting.get_candidate_at_index(2), Some(accounts.charlie));
+			assert_eq!(voting.get_candidate_at_index(3), None);
+			assert_eq!(voting.get_candidate_at_index(u32::MAX), None);
+		}
+
+		#[test]
+		fn payable_buy_ticket_refunds_excess() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 25);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(account_balance(accounts.alice), before + 5);
+			// 转入的金额刚好够时不退款
+			set_caller_with_value(accounts.alice, 4);
+			voting.buy_ticket(accounts.bob, 4).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 2);
+			assert_eq!(account_balance(accounts.alice), before + 5);
+		}
+
+		#[test]
+		fn payable_buy_ticket_charges_whole_tickets_only() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 21);
+			voting.buy_ticket(accounts.alice, 21).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			// 不足一张票的零头直接退回, 不再记入refund_dust
+			assert_eq!(account_balance(accounts.alice), before + 1);
+			assert_eq!(voting.claim_dust(), 0);
+			assert_eq!(account_balance(accounts.alice), before + 1);
+		}
+
+		#[test]
+		fn payable_buy_ticket_refunds_owner() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let alice_before = account_balance(accounts.alice);
+			let bob_before = account_balance(accounts.bob);
+			set_caller_with_value(accounts.alice, 25);
+			voting.buy_ticket(accounts.bob, 20).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 10);
+			assert_eq!(account_balance(accounts.bob), bob_before + 5);
+			assert_eq!(account_balance(accounts.alice), alice_before);
+		}
+
+		#[test]
+		fn payable_claim_refund_transfers_balance() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 20);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.refund_tickets(accounts.alice, 4).unwrap();
+			// 合约余额不足时转账失败, 待领金额保留
+			fund_contract(5);
+			assert_eq!(voting.claim_refund(), 0);
+			assert_eq!(account_balance(accounts.alice), before);
+			fund_contract(1000);
+			assert_eq!(voting.claim_refund(), 8);
+			assert_eq!(account_balance(accounts.alice), before + 8);
+			let contract = ink_env::account_id::<Environment>().expect("cannot get contract id");
+			assert_eq!(account_balance(contract), 992);
+			assert_eq!(voting.claim_refund(), 0);
+		}
+
+		#[test]
+		fn payable_claim_dust_transfers_balance() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			voting.refund_dust.insert(accounts.alice, 3);
+			assert_eq!(voting.claim_dust(), 3);
+			assert_eq!(account_balance(accounts.alice), before + 3);
+			assert_eq!(voting.claim_dust(), 0);
+		}
+
+		#[test]
+		fn payable_reset_election_refunds_uncast_tickets() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 20);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting.reset_election().unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			// 没投出的7张票按买票时的票价退回
+			assert_eq!(voting.claim_refund(), 14);
+			assert_eq!(account_balance(accounts.alice), before + 14);
+		}
+
+		#[test]
+		fn buy_ticket_without_required_payment_refunds_value() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 7);
+			voting.buy_ticket(accounts.alice, 5).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+			assert_eq!(account_balance(accounts.alice), before + 7);
+			let results = voting.batch_buy_tickets(ink_prelude::vec![(accounts.bob, 3)]);
+			assert_eq!(results, ink_prelude::vec![Ok(())]);
+			assert_eq!(account_balance(accounts.alice), before + 14);
+		}
+
+		#[test]
+		fn payable_refund_tickets_uses_paid_price() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 20);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.set_token_price(6).unwrap();
+			set_caller_with_value(accounts.alice, 12);
+			voting.buy_ticket(accounts.alice, 12).unwrap();
+			// 12张票共支付32, 平均每张票按32 / 12退款, 和当前票价无关
+			voting.refund_tickets(accounts.alice, 3).unwrap();
+			assert_eq!(voting.claim_refund(), 8);
+			assert_eq!(account_balance(accounts.alice), before + 8);
+			voting.set_token_price(1).unwrap();
+			voting.refund_tickets(accounts.alice, 9).unwrap();
+			assert_eq!(voting.claim_refund(), 24);
+			assert_eq!(account_balance(accounts.alice), before + 32);
+		}
+
+		#[test]
+		fn payable_refund_of_received_tickets_pays_nothing() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			set_caller_with_value(accounts.alice, 20);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting
+				.transfer_tickets(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			// bob没有支付过, 退票只把票放回票池
+			set_caller(accounts.bob);
+			let before = account_balance(accounts.bob);
+			voting.refund_tickets(accounts.bob, 4).unwrap();
+			assert_eq!(voting.claim_refund(), 0);
+			assert_eq!(account_balance(accounts.bob), before);
+			assert_eq!(voting.left_ticket_num(), 94);
+		}
+
+		#[test]
+		fn payable_buy_ticket_insufficient_payment_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 10);
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 20).unwrap_err(),
+				VotingError::InsufficientPayment
+			);
+			// 买票失败时全额退款
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(account_balance(accounts.alice), before + 10);
+		}
+
+		#[test]
+		fn payable_batch_buy_tickets_shares_payment() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					require_payment: true,
+					..default_config()
+				},
+			);
+			fund_contract(1000);
+			let before = account_balance(accounts.alice);
+			set_caller_with_value(accounts.alice, 30);
+			let results = voting.batch_buy_tickets(ink_prelude::vec![
+				(accounts.alice, 10),
+				(accounts.bob, 16),
+				(accounts.charlie, 10),
+			]);
+			assert_eq!(
+				results,
+				ink_prelude::vec![Ok(()), Ok(()), Err(VotingError::InsufficientPayment)]
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 8);
+			assert_eq!(voting.voter_ticket_balance(accounts.charlie), 0);
+			assert_eq!(account_balance(accounts.alice), before + 4);
+		}
+
+		#[test]
+		fn multisig_admin_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			let admins = ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie];
+			voting.set_admins(admins.clone(), 2).unwrap();
+			assert_eq!(voting.get_admins(), admins);
+			assert_eq!(voting.required_approvals(), 2);
+			// 开启多签后不能直接调用管理员操作
+			assert_eq!(
+				voting.add_candidate(accounts.django).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			let id = voting
+				.propose_action(
+					ACTION_ADD_CANDIDATE,
+					scale::Encode::encode(&accounts.django),
+				)
+				.unwrap();
+			// 只有发起人批准时不能执行
+			assert_eq!(
+				voting.execute_action(id).unwrap_err(),
+				VotingError::NotEnoughApprovals
+			);
+			assert_eq!(
+				voting.approve_action(id).unwrap_err(),
+				VotingError::AlreadyApproved
+			);
+			set_caller(accounts.charlie);
+			voting.approve_action(id).unwrap();
+			set_caller(accounts.bob);
+			voting.execute_action(id).unwrap();
+			assert!(voting.in_candidate_list.contains_key(&accounts.django));
+			assert_eq!(voting.get_pending_action(id), None);
+			assert_eq!(
+				voting.execute_action(id).unwrap_err(),
+				VotingError::ActionNotFound
+			);
+		}
+
+		#[test]
+		fn multisig_emergency_stop_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting
+				.set_admins(
+					ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie],
+					2,
+				)
+				.unwrap();
+			assert_eq!(
+				voting.emergency_stop().unwrap_err(),
+				VotingError::Unauthorized
+			);
+			let id = voting
+				.propose_action(ACTION_EMERGENCY_STOP, Vec::new())
+				.unwrap();
+			set_caller(accounts.bob);
+			voting.approve_action(id).unwrap();
+			voting.execute_action(id).unwrap();
+			assert!(voting.is_stopped());
+			assert_eq!(voting.get_pending_action(id), None);
+		}
+
+		#[test]
+		fn multisig_action_with_args_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting
+				.set_admins(ink_prelude::vec![accounts.alice, accounts.bob], 2)
+				.unwrap();
+			let mint = voting
+				.propose_action(ACTION_MINT_TOKENS, scale::Encode::encode(&50u128))
+				.unwrap();
+			let weight = voting
+				.propose_action(
+					ACTION_SET_VOTER_WEIGHT,
+					scale::Encode::encode(&(accounts.charlie, 3u128)),
+				)
+				.unwrap();
+			let invalid = voting
+				.propose_action(ACTION_MINT_TOKENS, Vec::new())
+				.unwrap();
+			set_caller(accounts.bob);
+			for id in [mint, weight, invalid].iter() {
+				voting.approve_action(*id).unwrap();
+			}
+			voting.execute_action(mint).unwrap();
+			voting.execute_action(weight).unwrap();
+			assert_eq!(voting.all_ticket_num(), 150);
+			assert_eq!(voting.get_voter_weight(accounts.charlie), 3);
+			assert_eq!(
+				voting.execute_action(invalid).unwrap_err(),
+				VotingError::InvalidActionArgs
+			);
+			// 每个需要管理员权限的消息都可以通过多签操作调用
+			let take_snapshot = voting
+				.propose_action(ACTION_TAKE_SNAPSHOT, Vec::new())
+				.unwrap();
+			set_caller(accounts.alice);
+			voting.approve_action(take_snapshot).unwrap();
+			voting.execute_action(take_snapshot).unwrap();
+			assert!(voting.get_snapshot(0).is_some());
+		}
+
+		#[test]
+		fn multisig_transfer_admin_replaces_named_admin() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting
+				.set_admins(
+					ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie],
+					2,
+				)
+				.unwrap();
+			let transfer = voting
+				.propose_action(
+					ACTION_TRANSFER_ADMIN,
+					scale::Encode::encode(&(accounts.charlie, accounts.eve)),
+				)
+				.unwrap();
+			let not_admin = voting
+				.propose_action(
+					ACTION_TRANSFER_ADMIN,
+					scale::Encode::encode(&(accounts.django, accounts.eve)),
+				)
+				.unwrap();
+			set_caller(accounts.bob);
+			voting.approve_action(transfer).unwrap();
+			voting.approve_action(not_admin).unwrap();
+			assert_eq!(
+				voting.execute_action(not_admin).unwrap_err(),
+				VotingError::InvalidActionArgs
+			);
+			// 由bob执行, 但转出的是操作参数中的charlie
+			voting.execute_action(transfer).unwrap();
+			set_caller(accounts.eve);
+			voting.accept_admin().unwrap();
+			assert_eq!(
+				voting.get_admins(),
+				ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve]
+			);
+		}
+
+		#[test]
+		fn multisig_action_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting
+				.set_admins(
+					ink_prelude::vec![accounts.alice, accounts.bob, accounts.charlie],
+					2,
+				)
+				.unwrap();
+			let id = voting.propose_action(ACTION_PAUSE, Vec::new()).unwrap();
+			assert_eq!(
+				voting.propose_action([0xff; 4], Vec::new()).unwrap_err(),
+				VotingError::UnknownAction
+			);
+			set_caller(accounts.django);
+			assert_eq!(
+				voting.propose_action(ACTION_PAUSE, Vec::new()).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(
+				voting.approve_action(id).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(
+				voting.execute_action(id).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert!(!voting.is_paused());
+		}
+
+		#[test]
+		fn set_admins_invalid_threshold_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.set_admins(Vec::new(), 1).unwrap_err(),
+				VotingError::InvalidApprovalThreshold
+			);
+			assert_eq!(
+				voting
+					.set_admins(ink_prelude::vec![accounts.alice, accounts.bob], 3)
+					.unwrap_err(),
+				VotingError::InvalidApprovalThreshold
+			);
+			assert_eq!(
+				voting
+					.set_admins(ink_prelude::vec![accounts.alice, accounts.alice], 1)
+					.unwrap_err(),
+				VotingError::InvalidApprovalThreshold
+			);
+			assert_eq!(voting.get_admins(), ink_prelude::vec![accounts.alice]);
+			assert_eq!(voting.required_approvals(), 1);
+		}
+
+		#[test]
+		fn auction_price_rises_with_sales() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					auction_step: 10,
+					..default_config()
+				},
+			);
+			let buyers = [
+				accounts.alice,
+				accounts.bob,
+				accounts.charlie,
+				accounts.django,
+			];
+			let mut prices = Vec::new();
+			let mut tickets = Vec::new();
+			for buyer in buyers.iter() {
+				prices.push(voting.price_of_ticket());
+				voting.buy_ticket(*buyer, 10).unwrap();
+				tickets.push(voting.voter_ticket_balance(*buyer));
+			}
+			// 同样的金额买到的票越来越少
+			assert_eq!(prices, ink_prelude::vec![1, 2, 2, 3]);
+			assert_eq!(tickets, ink_prelude::vec![10, 5, 5, 3]);
+			assert_eq!(voting.price_of_ticket(), 3);
+			set_caller(accounts.django);
+			assert_eq!(voting.claim_dust(), 1);
+		}
+
+		#[test]
+		fn events_include_election_id() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					election_id: 7,
+					..default_config()
+				},
+			);
+			assert_eq!(voting.get_election_id(), 7);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting.mint_tokens(5).unwrap();
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			assert_eq!(emitted_events.len(), 3);
+			for event in emitted_events.iter() {
+				let decoded = <Event as scale::Decode>::decode(&mut &event.data[..])
+					.expect("encountered invalid contract event data buffer");
+				let election_id = match decoded {
+					Event::PhaseAdvanced(PhaseAdvanced { election_id, .. }) => election_id,
+					Event::VoteCast(VoteCast { election_id, .. }) => election_id,
+					Event::TokensMinted(TokensMinted { election_id, .. }) => election_id,
+					_ => panic!("encountered unexpected event kind"),
+				};
+				assert_eq!(election_id, 7);
+			}
+		}
+
+		#[test]
+		fn stake_for_tickets_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					token_price: 2,
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			let contract = ink_env::account_id::<Environment>().expect("cannot get contract id");
+			set_mock_balance(accounts.frank, accounts.alice, 50);
+			// 只转走整数张票对应的代币
+			voting.stake_for_tickets(25).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 12);
+			assert_eq!(voting.staked_of(accounts.alice), 24);
+			assert_eq!(voting.left_ticket_num(), 88);
+			assert_eq!(mock_balance_of(accounts.frank, accounts.alice), 26);
+			assert_eq!(mock_balance_of(accounts.frank, contract), 24);
+			// 投票结束前不能取回
+			assert_eq!(voting.unstake().unwrap_err(), VotingError::WrongPhase);
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 12)
+				.unwrap();
+			assert_eq!(voting.unstake().unwrap_err(), VotingError::WrongPhase);
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.unstake(), Ok(24));
+			assert_eq!(voting.staked_of(accounts.alice), 0);
+			assert_eq!(mock_balance_of(accounts.frank, accounts.alice), 50);
+			assert_eq!(mock_balance_of(accounts.frank, contract), 0);
+			assert_eq!(voting.unstake().unwrap_err(), VotingError::ZeroAmount);
+			// 投出的票仍然有效
+			assert_eq!(voting.total_votes_for(accounts.bob), 12);
+		}
+
+		#[test]
+		fn stake_for_tickets_without_tokens_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates.clone(), default_config());
+			assert_eq!(
+				voting.stake_for_tickets(10).unwrap_err(),
+				VotingError::StakingDisabled
+			);
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 5);
+			assert_eq!(
+				voting.stake_for_tickets(10).unwrap_err(),
+				VotingError::TokenCallFailed
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.staked_of(accounts.alice), 0);
+			assert_eq!(voting.left_ticket_num(), 100);
+			assert_eq!(mock_balance_of(accounts.frank, accounts.alice), 5);
+		}
+
+		#[test]
+		fn transfer_staked_tickets_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 50);
+			voting.stake_for_tickets(20).unwrap();
+			voting.buy_ticket(accounts.alice, 5).unwrap();
+			assert_eq!(voting.staked_tickets_of(accounts.alice), 20);
+			// 只有买来的5张票可以转让
+			assert_eq!(
+				voting
+					.transfer_tickets(accounts.alice, accounts.bob, 6)
+					.unwrap_err(),
+				VotingError::StakedTicketsLocked
+			);
+			voting
+				.transfer_tickets(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 20);
+			// 投票后撤回的票重新锁定
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 20)
+				.unwrap();
+			assert_eq!(voting.staked_tickets_of(accounts.alice), 0);
+			voting.revoke_vote(accounts.alice, accounts.bob, 8).unwrap();
+			assert_eq!(voting.staked_tickets_of(accounts.alice), 8);
+			assert_eq!(
+				voting
+					.transfer_tickets(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::StakedTicketsLocked
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 5);
+		}
+
+		#[test]
+		fn bought_tickets_are_not_locked_after_staked_tickets_are_cast() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 50);
+			voting.stake_for_tickets(10).unwrap();
+			voting.buy_ticket(accounts.alice, 5).unwrap();
+			voting.advance_phase().unwrap();
+			// 投票先花费质押换到的票, 之后买来的票可以自由转让和退回
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 12)
+				.unwrap();
+			assert_eq!(voting.staked_tickets_of(accounts.alice), 0);
+			voting
+				.transfer_tickets(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			voting.refund_tickets(accounts.alice, 1).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 1);
+			// 撤回的票中只有10张是质押票, 其余仍可转让
+			voting
+				.revoke_vote(accounts.alice, accounts.bob, 12)
+				.unwrap();
+			assert_eq!(voting.staked_tickets_of(accounts.alice), 10);
+			voting
+				.transfer_tickets(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(
+				voting
+					.transfer_tickets(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::StakedTicketsLocked
+			);
+		}
+
+		#[test]
+		fn unstake_after_emergency_stop_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 50);
+			voting.stake_for_tickets(10).unwrap();
+			voting.buy_ticket(accounts.alice, 3).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 4)
+				.unwrap();
+			assert_eq!(voting.unstake(), Err(VotingError::WrongPhase));
+			voting.emergency_stop().unwrap();
+			// 只退回买来的票, 质押的票随unstake作废
+			assert_eq!(voting.claim_emergency_refund(), 3);
+			assert_eq!(voting.unstake(), Ok(10));
+			assert_eq!(voting.staked_tickets_of(accounts.alice), 0);
+			assert_eq!(mock_balance_of(accounts.frank, accounts.alice), 50);
+		}
+
+		#[test]
+		fn unstake_with_uncast_staked_tickets_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 50);
+			voting.stake_for_tickets(10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 9)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.unstake().unwrap_err(), VotingError::UncastTickets);
+			assert_eq!(voting.staked_of(accounts.alice), 10);
+		}
+
+		#[test]
+		fn unstaked_votes_cannot_be_revoked() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 50);
+			voting.stake_for_tickets(10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate(accounts.alice, accounts.bob, 10)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.unstake(), Ok(10));
+			// 取回质押后投出的票不能再撤回, 不能用同一笔代币重复换票
+			assert_eq!(
+				voting
+					.revoke_vote(accounts.alice, accounts.bob, 10)
+					.unwrap_err(),
+				VotingError::WrongPhase
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.total_votes_for(accounts.bob), 10);
+		}
+
+		#[test]
+		fn delegate_staked_tickets_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 50);
+			voting.stake_for_tickets(20).unwrap();
+			assert_eq!(
+				voting
+					.delegate_vote(accounts.alice, accounts.bob)
+					.unwrap_err(),
+				VotingError::StakedTicketsLocked
+			);
+			assert_eq!(voting.delegate_of(accounts.alice), None);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 20);
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 0);
+			assert_eq!(voting.unstake().unwrap_err(), VotingError::WrongPhase);
+			assert_eq!(voting.staked_of(accounts.alice), 20);
+		}
+
+		#[test]
+		fn refund_staked_tickets_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					stake_token: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			set_mock_balance(accounts.frank, accounts.alice, 50);
+			voting.stake_for_tickets(20).unwrap();
+			voting.buy_ticket(accounts.alice, 3).unwrap();
+			assert_eq!(
+				voting.refund_tickets(accounts.alice, 4).unwrap_err(),
+				VotingError::StakedTicketsLocked
+			);
+			voting.refund_tickets(accounts.alice, 3).unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 20);
+			assert_eq!(voting.left_ticket_num(), 80);
+			assert_eq!(voting.unstake().unwrap_err(), VotingError::WrongPhase);
+			assert_eq!(voting.staked_of(accounts.alice), 20);
+		}
+
+		#[test]
+		fn take_snapshot_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			test::advance_block::<Environment>().unwrap();
+			let block = ink_env::block_number::<Environment>().unwrap();
+			assert_eq!(voting.take_snapshot(), Ok(0));
+			let snapshot = voting.get_snapshot(0).unwrap();
+			assert_eq!(snapshot.len(), 2);
+			assert_eq!(snapshot[0].candidate, accounts.alice);
+			assert_eq!(snapshot[0].vote, 0);
+			assert_eq!(snapshot[1].candidate, accounts.bob);
+			assert_eq!(snapshot[1].vote, 2);
+			assert_eq!(voting.get_snapshot_block(0), Some(block));
+			assert_eq!(voting.get_snapshot(1), None);
+			assert_eq!(voting.get_snapshot_block(1), None);
+		}
+
+		#[test]
+		fn take_snapshot_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.bob);
+			assert_eq!(voting.take_snapshot(), Err(VotingError::Unauthorized));
+			assert_eq!(voting.get_snapshot(0), None);
+		}
+
+		#[test]
+		fn stale_snapshot_is_unchanged() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(voting.take_snapshot(), Ok(0));
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(voting.take_snapshot(), Ok(1));
+			assert_eq!(voting.get_snapshot(0).unwrap()[1].vote, 2);
+			assert_eq!(voting.get_snapshot(1).unwrap()[1].vote, 5);
+			assert_eq!(voting.total_votes_for(accounts.bob), 5);
+		}
+
+		#[test]
+		fn take_snapshot_over_limit_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					max_snapshots: 2,
+					..default_config()
+				},
+			);
+			assert_eq!(voting.take_snapshot(), Ok(0));
+			assert_eq!(voting.take_snapshot(), Ok(1));
+			assert_eq!(
+				voting.take_snapshot().unwrap_err(),
+				VotingError::SnapshotLimitReached
+			);
+			assert!(voting.get_snapshot(1).is_some());
+			assert_eq!(voting.get_snapshot(2), None);
+		}
+
+		#[test]
+		fn revoke_vote_partially_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			voting.revoke_vote(accounts.alice, accounts.bob, 2).unwrap();
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 3);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 7);
+		}
+
+		#[test]
+		fn flash_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					flash_hook_contract: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			voting.flash_vote(accounts.alice, accounts.bob, 5).unwrap();
+			// 回调时闪电投票的票已经计入候选人票数
+			assert_eq!(
+				mock_hook_calls(),
+				ink_prelude::vec![(accounts.frank, accounts.alice, accounts.bob, 5, 7)]
+			);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 2);
+			assert_eq!(voting.total_votes_for(accounts.bob), 2);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 8);
+		}
+
+		#[test]
+		fn flash_vote_leaves_no_vote_records() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					flash_hook_contract: Some(accounts.frank),
+					max_history_blocks: 10,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			let voted_at = voting
+				.vote_timestamps
+				.get(&(accounts.alice, accounts.bob))
+				.copied();
+			let history = voting.get_votes_since_block(0);
+			test::advance_block::<Environment>().unwrap();
+			voting.flash_vote(accounts.alice, accounts.bob, 5).unwrap();
+			voting
+				.flash_vote(accounts.alice, accounts.alice, 3)
+				.unwrap();
+			// 得票速度, 投票时间, 区块记录和回执都不受闪电投票影响
+			assert_eq!(voting.get_candidate_velocity(accounts.bob, 10), 2);
+			assert_eq!(voting.get_candidate_velocity(accounts.alice, 10), 0);
+			assert_eq!(
+				voting
+					.vote_timestamps
+					.get(&(accounts.alice, accounts.bob))
+					.copied(),
+				voted_at
+			);
+			assert_eq!(
+				voting
+					.vote_timestamps
+					.get(&(accounts.alice, accounts.alice)),
+				None
+			);
+			assert_eq!(voting.get_votes_since_block(0), history);
+			assert_eq!(voting.get_voter_receipts(accounts.alice).len(), 1);
+		}
+
+		#[test]
+		fn flash_vote_rejected_by_hook_is_revoked() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					flash_hook_contract: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			MOCK_REJECTING_HOOKS.with(|x| x.borrow_mut().push(accounts.frank));
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.flash_vote(accounts.alice, accounts.bob, 5),
+				Err(VotingError::FlashHookFailed)
+			);
+			assert_eq!(mock_hook_calls().len(), 1);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+		}
+
+		#[test]
+		fn flash_vote_without_hook_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting.flash_vote(accounts.alice, accounts.bob, 5),
+				Err(VotingError::FlashHookNotSet)
+			);
+			assert_eq!(mock_hook_calls().len(), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+		}
+
+		#[test]
+		fn revoke_vote_fully_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 5)
+				.unwrap();
+			voting.revoke_vote(accounts.alice, accounts.bob, 5).unwrap();
+			assert!(!voting
+				.vote_num
+				.contains_key(&(accounts.alice, accounts.bob)));
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 0);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+		}
+
+		#[test]
+		fn revoke_more_than_cast_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.eve, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.eve, accounts.bob, 5)
+				.unwrap();
+			assert_eq!(
+				voting
+					.revoke_vote(accounts.alice, accounts.bob, 3)
+					.unwrap_err(),
+				VotingError::InsufficientVotes
+			);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 2);
+			assert_eq!(voting.total_votes_for(accounts.bob), 7);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 8);
+		}
+
+		#[test]
+		fn revoke_others_vote_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.eve, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.eve, accounts.bob, 2)
+				.unwrap();
+			assert_eq!(
+				voting
+					.revoke_vote(accounts.eve, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+		}
+
+		#[test]
+		fn delegate_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.eve, 5).unwrap();
+			voting.delegate_vote(accounts.alice, accounts.eve).unwrap();
+			assert_eq!(voting.delegate_of(accounts.alice), Some(accounts.eve));
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.eve), 15);
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.eve, accounts.bob, 15)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 15);
+		}
+
+		#[test]
+		fn delegated_voter_cannot_buy_or_vote() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.delegate_vote(accounts.alice, accounts.eve).unwrap();
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 10).unwrap_err(),
+				VotingError::AlreadyDelegated
+			);
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::AlreadyDelegated
+			);
+			assert_eq!(
+				voting
+					.delegate_vote(accounts.alice, accounts.bob)
+					.unwrap_err(),
+				VotingError::AlreadyDelegated
+			);
+		}
+
+		#[test]
+		fn delegate_vote_follows_chain() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.bob, 3).unwrap();
+			set_caller(accounts.bob);
+			voting.delegate_vote(accounts.bob, accounts.eve).unwrap();
+			set_caller(accounts.alice);
+			voting.delegate_vote(accounts.alice, accounts.bob).unwrap();
+			assert_eq!(voting.delegate_of(accounts.alice), Some(accounts.bob));
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.eve), 13);
+		}
+
+		#[test]
+		fn circular_delegation_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting
+					.delegate_vote(accounts.alice, accounts.alice)
+					.unwrap_err(),
+				VotingError::CircularDelegation
+			);
+			set_caller(accounts.bob);
+			voting.delegate_vote(accounts.bob, accounts.eve).unwrap();
+			set_caller(accounts.eve);
+			voting.delegate_vote(accounts.eve, accounts.alice).unwrap();
+			set_caller(accounts.alice);
+			assert_eq!(
+				voting
+					.delegate_vote(accounts.alice, accounts.bob)
+					.unwrap_err(),
+				VotingError::CircularDelegation
+			);
+			assert_eq!(voting.delegate_of(accounts.alice), None);
+		}
+
+		#[test]
+		fn delegate_others_vote_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting
+					.delegate_vote(accounts.bob, accounts.eve)
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+		}
+
+		#[test]
+		fn notify_expiring_tickets_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			test::set_block_timestamp::<Environment>(0);
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					warning_window_ms: 100,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			// 距离截止还有100毫秒, 不在提醒范围内
+			test::set_block_timestamp::<Environment>(900);
+			assert!(!voting.notify_expiring_tickets(accounts.alice));
+			test::set_block_timestamp::<Environment>(950);
+			assert!(voting.notify_expiring_tickets(accounts.alice));
+			// 手中没有票时不提醒
+			assert!(!voting.notify_expiring_tickets(accounts.bob));
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			assert_eq!(emitted_events.len(), 1);
+			let decoded = <Event as scale::Decode>::decode(&mut &emitted_events[0].data[..])
+				.expect("encountered invalid contract event data buffer");
+			if let Event::TicketsAboutToExpire(TicketsAboutToExpire {
+				voter,
+				balance,
+				ms_remaining,
+				..
+			}) = decoded
+			{
+				assert_eq!(voter, accounts.alice);
+				assert_eq!(balance, 10);
+				assert_eq!(ms_remaining, 50);
+			} else {
+				panic!("encountered unexpected event kind: expected a TicketsAboutToExpire event")
+			}
+			// 截止后不再提醒
+			test::set_block_timestamp::<Environment>(1001);
+			assert!(!voting.notify_expiring_tickets(accounts.alice));
+		}
+
+		#[test]
+		fn voting_blocked_after_deadline() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			test::set_block_timestamp::<Environment>(0);
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			test::set_block_timestamp::<Environment>(1000);
+			assert!(!voting.has_ended());
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			test::set_block_timestamp::<Environment>(1001);
+			assert!(voting.has_ended());
+			assert_eq!(
+				voting.buy_ticket(accounts.alice, 10).unwrap_err(),
+				VotingError::VotingEnded
+			);
+			assert_eq!(
+				voting
+					.vote_candidate(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::VotingEnded
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 9);
+			assert_eq!(voting.total_votes_for(accounts.bob), 1);
+		}
+
+		#[test]
+		fn large_ticket_amounts_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let total = u32::MAX as u128 * 4;
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					total_tokens: total,
+					token_price: 2,
+					..default_config()
+				},
+			);
+			voting
+				.buy_ticket(accounts.alice, u32::MAX as u128 * 6)
+				.unwrap();
+			assert_eq!(
+				voting.voter_ticket_balance(accounts.alice),
+				u32::MAX as u128 * 3
+			);
+			assert_eq!(voting.left_ticket_num(), u32::MAX as u128);
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, u32::MAX as u128 + 1)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), u32::MAX as u128 + 1);
+			assert_eq!(
+				voting.buy_ticket(accounts.eve, u128::MAX).unwrap_err(),
+				VotingError::SoldOut
+			);
 		}
 
-		// 投票
-		// owner 投票人
-		// candidate 被投票人
-		// amout 投票数量
-		#[ink(message)]
-		pub fn vote_candidate(&mut self, owner: AccountId, candidate: AccountId, amout: u32) -> bool {
-			let ret: bool = self.vote_candidate_without_event(owner, candidate, amout);
-			if ret {
-				self.env().emit_event(VoteEvent {
-					from: self.env().caller(),
-					to: candidate,
-				});
-			}
-			ret
+		#[test]
+		fn linear_vote_costs_one_ticket_per_vote() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.voting_mode(), VotingMode::Linear);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 7);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 3);
 		}
 
-		// it seems unit test failed when emit event if call vote_candidate function directly
-		fn vote_candidate_without_event(
-			&mut self,
-			owner: AccountId,
-			candidate: AccountId,
-			amout: u32,
-		) -> bool {
-			// 1. 首先确认被投票人在candidate_list中
-			if !self.in_candidate_list.contains_key(&candidate) {
-				return false;
-			}
-			// 2. 确认投票人有足够的票数
-			let ticket_num = self.voter_ticket_balance(owner);
-			if ticket_num < amout {
-				return false;
+		#[test]
+		fn quadratic_vote_costs_square_of_votes() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					mode: VotingMode::Quadratic,
+					..default_config()
+				},
+			);
+			assert_eq!(voting.voting_mode(), VotingMode::Quadratic);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 1);
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 3);
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+					.unwrap_err(),
+				VotingError::InsufficientTickets
+			);
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+		}
+
+		#[test]
+		fn quadratic_vote_cost_is_cumulative() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					mode: VotingMode::Quadratic,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 20).unwrap();
+			voting.advance_phase().unwrap();
+			// 分三次各投1票, 依次花费1, 3, 5张票, 共9张
+			for _ in 0..3 {
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+					.unwrap();
 			}
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 11);
+			// 再投2票花费5²-3²=16张, 票不够
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+					.unwrap_err(),
+				VotingError::InsufficientTickets
+			);
+			// 投给其他候选人从零开始计算
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 3)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 2);
+		}
 
-			// 3. 投票者票数减少
-			self.voter_balance.entry(owner).and_modify(|v| *v -= amout);
-			// 4. 更新voter
-			self
-				.vote_num
-				.entry((owner, candidate))
-				.and_modify(|v| *v += amout)
-				.or_insert(amout);
-			// 5. 候选人票数增加
-			self
-				.votes_received
-				.entry(candidate)
-				.and_modify(|v| *v += amout)
-				.or_insert(amout);
-			true
+		#[test]
+		fn quadratic_vote_cannot_be_revoked() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					mode: VotingMode::Quadratic,
+					flash_hook_contract: Some(accounts.frank),
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(
+				voting
+					.revoke_vote(accounts.alice, accounts.bob, 3)
+					.unwrap_err(),
+				VotingError::RevokeNotSupported
+			);
+			assert_eq!(
+				voting
+					.flash_vote(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::RevokeNotSupported
+			);
+			assert!(mock_hook_calls().is_empty());
+			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 1);
 		}
 
-		// 获取某用户被投票的数量
-		#[ink(message)]
-		pub fn total_votes_for(&self, candidate: AccountId) -> u32 {
-			self.my_value_or_zero(candidate)
+		#[test]
+		fn quadratic_vote_overflow_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(
+				candidates,
+				VotingConfig {
+					mode: VotingMode::Quadratic,
+					..default_config()
+				},
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(
+				voting
+					.vote_candidate_without_event(accounts.alice, accounts.bob, u128::MAX)
+					.unwrap_err(),
+				VotingError::Overflow
+			);
 		}
 
-		// 获取某用户被投票的数量
-		#[ink(message)]
-		pub fn callee_vote_of(&self, callee: AccountId, candidate: AccountId) -> u32 {
-			*self.vote_num.get(&(callee, candidate)).unwrap_or(&0)
+		#[test]
+		fn voting_core_trait_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates.clone(), default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(VotingCore::get_candidates(&voting), candidates);
+			assert!(VotingCore::vote_candidate(&mut voting, accounts.bob, 3));
+			assert!(!VotingCore::vote_candidate(&mut voting, accounts.bob, 8));
+			assert!(!VotingCore::vote_candidate(&mut voting, accounts.eve, 1));
+			assert_eq!(VotingCore::total_votes_for(&voting, accounts.bob), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 7);
+			assert_eq!(
+				VotingCore::get_current_votes(&voting),
+				ink_prelude::vec![(accounts.alice, 0), (accounts.bob, 3)]
+			);
 		}
 
-		// 内部辅助函数用户确认某用户是否被存在candidate_list中
-		fn valid_candidate(&self, candidate: AccountId) -> bool {
-			for x in self.candidate_list.into_iter() {
-				if *x == candidate {
-					return true;
-				}
-			}
-			return false;
+		#[test]
+		fn proxy_vote_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.set_proxy_contract(Some(accounts.django)).unwrap();
+			assert_eq!(voting.proxy_contract(), Some(accounts.django));
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			set_caller(accounts.django);
+			assert!(VoteProxy::proxy_vote(
+				&mut voting,
+				accounts.charlie,
+				accounts.bob,
+				3
+			));
+			assert!(!VoteProxy::proxy_vote(
+				&mut voting,
+				accounts.charlie,
+				accounts.bob,
+				8
+			));
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			assert_eq!(voting.voter_ticket_balance(accounts.charlie), 7);
 		}
 
-		// 内部辅助函数用户获取某用户的投票数量
-		fn my_value_or_zero(&self, of: AccountId) -> u32 {
-			let value = self.votes_received.get(&of).unwrap_or(&0);
-			*value
+		#[test]
+		fn proxy_vote_from_unregistered_caller_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			// 还没有登记代理合约
+			assert!(!VoteProxy::proxy_vote(
+				&mut voting,
+				accounts.charlie,
+				accounts.bob,
+				1
+			));
+			voting.set_proxy_contract(Some(accounts.django)).unwrap();
+			set_caller(accounts.eve);
+			assert!(!VoteProxy::proxy_vote(
+				&mut voting,
+				accounts.charlie,
+				accounts.bob,
+				1
+			));
+			assert_eq!(
+				voting.set_proxy_contract(Some(accounts.eve)).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			set_caller(accounts.alice);
+			voting.set_proxy_contract(None).unwrap();
+			set_caller(accounts.django);
+			assert!(!VoteProxy::proxy_vote(
+				&mut voting,
+				accounts.charlie,
+				accounts.bob,
+				1
+			));
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
 		}
-	}
 
-	#[cfg(test)]
-	mod tests {
-		use super::*;
-		use ink_env::test;
-		use ink_prelude::vec::Vec;
-		use ink_storage::collections::Vec as StorageVec;
-		type Accounts = test::DefaultAccounts<Environment>;
-		fn default_accounts() -> Accounts {
-			test::default_accounts().expect("Test environment is expected to be initialized.")
+		#[test]
+		fn get_winner_without_votes_is_none() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.advance_phase().unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_winner(), Ok(None));
 		}
+
 		#[test]
-		fn default_works() {
-			let mut voting = Voting::new(Vec::new(), 100, 1);
-			assert_eq!(voting.candidate_list, StorageVec::new());
-			assert_eq!(voting.candidate_list.len(), 0);
-			assert_eq!(voting.get_candidates_len(), 0);
-			assert_eq!(voting.all_ticket_num(), 100);
-			assert_eq!(voting.left_ticket_num(), 100);
-			assert_eq!(voting.price_of_ticket(), 1);
+		fn get_winner_single_candidate() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_winner(), Ok(Some(accounts.bob)));
 		}
 
 		#[test]
-		fn init_candidates() {
+		fn get_winner_tie_returns_earliest_candidate() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
-			let mut voting = Voting::new(candidates, 100, 1);
-			assert_eq!(voting.candidate_list.len(), 3);
-			assert_eq!(voting.all_ticket_num(), 100);
-			assert_eq!(voting.left_ticket_num(), 100);
-			assert_eq!(voting.price_of_ticket(), 1);
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.eve, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 2)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_winner(), Ok(Some(accounts.bob)));
 		}
 
 		#[test]
-		fn buy_works() {
+		fn get_winner_works() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
-			let mut voting = Voting::new(candidates, 100, 1);
-			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
-			assert_eq!(voting.left_ticket_num(), 100);
-			assert_eq!(voting.buy_ticket(accounts.alice, 1), true);
-			assert_eq!(voting.left_ticket_num(), 99);
-			assert_eq!(voting.voter_ticket_balance(accounts.alice), 1);
-			assert_eq!(voting.buy_ticket(accounts.alice, 1), true);
-			assert_eq!(voting.left_ticket_num(), 98);
-			assert_eq!(voting.voter_ticket_balance(accounts.alice), 2);
-			assert_eq!(voting.buy_ticket(accounts.bob, 1), true);
-			assert_eq!(voting.left_ticket_num(), 97);
-			assert_eq!(voting.voter_ticket_balance(accounts.alice), 2);
-			assert_eq!(voting.voter_ticket_balance(accounts.bob), 1);
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 2)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.eve, 3)
+				.unwrap();
+			voting.advance_phase().unwrap();
+			assert_eq!(voting.get_winner(), Ok(Some(accounts.eve)));
 		}
 
 		#[test]
-		fn voter_balance_work() {
+		fn get_top_n_candidates_works() {
 			let accounts = default_accounts();
-			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
-			let mut voting = Voting::new(candidates, 100, 1);
-			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
-			assert_eq!(voting.buy_ticket(accounts.alice, 10), true);
-			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			let candidates = ink_prelude::vec![
+				accounts.alice,
+				accounts.bob,
+				accounts.charlie,
+				accounts.django,
+				accounts.eve
+			];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.eve, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.charlie, 1)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 1)
+				.unwrap();
+			let top = voting.get_top_n_candidates(3);
+			assert_eq!(top.len(), 3);
+			assert_eq!(top[0].candidate, accounts.eve);
+			assert_eq!(top[0].vote, 3);
+			// 平票时按加入顺序排列
+			assert_eq!(top[1].candidate, accounts.bob);
+			assert_eq!(top[1].vote, 1);
+			assert_eq!(top[2].candidate, accounts.charlie);
+			assert_eq!(top[2].vote, 1);
 		}
 
 		#[test]
-		fn vote_works() {
+		fn get_top_n_candidates_clamps_n() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
-			let mut voting = Voting::new(candidates, 100, 1);
-			assert_eq!(voting.buy_ticket(accounts.alice, 10), true);
-			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
-			assert_eq!(voting.total_votes_for(accounts.bob), 0);
-			assert_eq!(
-				voting.vote_candidate_without_event(accounts.alice, accounts.bob, 1),
-				true
-			);
-			assert_eq!(voting.voter_ticket_balance(accounts.alice), 9);
-			assert_eq!(voting.total_votes_for(accounts.bob), 1);
-			assert_eq!(voting.callee_vote_of(accounts.alice, accounts.bob), 1);
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(voting.get_top_n_candidates(0).len(), 0);
+			let top = voting.get_top_n_candidates(10);
+			assert_eq!(top.len(), 3);
+			assert_eq!(top[0].candidate, accounts.alice);
+			assert_eq!(top[1].candidate, accounts.bob);
+			assert_eq!(top[2].candidate, accounts.eve);
 		}
 
 		#[test]
-		fn vote_invalid_candidate_does_not_work() {
+		fn transfer_tickets_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.buy_ticket(accounts.bob, 5).unwrap();
+			// 转给已有票的用户
+			voting
+				.transfer_tickets(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 7);
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 8);
+			// 转给新用户
+			voting
+				.transfer_tickets(accounts.alice, accounts.eve, 7)
+				.unwrap();
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 0);
+			assert_eq!(voting.voter_ticket_balance(accounts.eve), 7);
+			assert_eq!(voting.left_ticket_num(), 85);
+		}
+
+		#[test]
+		fn transfer_zero_tickets_does_not_work() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
-			let mut voting = Voting::new(candidates, 100, 1);
-			assert_eq!(voting.buy_ticket(accounts.alice, 10), true);
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
 			assert_eq!(
-				voting.vote_candidate_without_event(accounts.alice, accounts.eve, 1),
-				false
+				voting
+					.transfer_tickets(accounts.alice, accounts.bob, 0)
+					.unwrap_err(),
+				VotingError::ZeroAmount
 			);
 		}
 
 		#[test]
-		fn ticket_not_enough_does_not_work() {
+		fn transfer_more_than_balance_does_not_work() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
-			let mut voting = Voting::new(candidates, 100, 1);
-			assert_eq!(voting.buy_ticket(accounts.alice, 1), true);
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
 			assert_eq!(
-				voting.vote_candidate_without_event(accounts.alice, accounts.bob, 2),
-				false
+				voting
+					.transfer_tickets(accounts.alice, accounts.bob, 11)
+					.unwrap_err(),
+				VotingError::InsufficientTickets
 			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 10);
+			assert_eq!(voting.voter_ticket_balance(accounts.bob), 0);
 		}
 
 		#[test]
-		fn anyone_could_buy_ticket() {
+		fn transfer_others_tickets_does_not_work() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
-			let mut voting = Voting::new(candidates, 100, 1);
-			assert_eq!(voting.buy_ticket(accounts.eve, 10), true);
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.bob, 10).unwrap();
+			assert_eq!(
+				voting
+					.transfer_tickets(accounts.bob, accounts.alice, 1)
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
 		}
 
 		#[test]
 		fn get_current_votes_works() {
 			let accounts = default_accounts();
 			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
-			let mut voting = Voting::new(candidates.clone(), 100, 1);
+			let mut voting = Voting::new(candidates.clone(), default_config());
 			let current = voting.get_current_votes();
 			assert_eq!(current.len(), 3);
 			assert_eq!(current[0].vote, 0);
 			assert_eq!(current[1].vote, 0);
 			assert_eq!(current[2].vote, 0);
-			assert_eq!(voting.buy_ticket(accounts.alice, 10), true);
-			voting.vote_candidate_without_event(accounts.alice, accounts.alice, 1);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.alice, 1)
+				.unwrap();
 			let current = voting.get_current_votes();
 			assert_eq!(current.len(), 3);
 			assert_eq!(current[0].vote, 1);
 			assert_eq!(current[1].vote, 0);
 			assert_eq!(current[2].vote, 0);
 		}
+
+		#[test]
+		fn add_candidate_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.add_candidate(accounts.eve).unwrap();
+			assert_eq!(voting.get_candidates_len(), 3);
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve]
+			);
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.eve, 1)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.eve), 1);
+		}
+
+		#[test]
+		fn add_existing_candidate_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.add_candidate(accounts.bob).unwrap_err(),
+				VotingError::CandidateAlreadyExists
+			);
+			voting.add_candidate(accounts.eve).unwrap();
+			assert_eq!(
+				voting.add_candidate(accounts.eve).unwrap_err(),
+				VotingError::CandidateAlreadyExists
+			);
+			assert_eq!(voting.get_candidates_len(), 3);
+		}
+
+		#[test]
+		fn batch_add_candidates_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			let added = voting
+				.batch_add_candidates(ink_prelude::vec![
+					accounts.charlie,
+					accounts.bob,
+					accounts.django,
+					accounts.charlie,
+				])
+				.unwrap();
+			// bob已经是候选人, charlie重复出现, 都只算一次
+			assert_eq!(added, 2);
+			assert_eq!(voting.candidate_list.len(), 4);
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![
+					accounts.alice,
+					accounts.bob,
+					accounts.charlie,
+					accounts.django
+				]
+			);
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			assert_eq!(emitted_events.len(), 1);
+			let decoded = <Event as scale::Decode>::decode(&mut &emitted_events[0].data[..])
+				.expect("encountered invalid contract event data buffer");
+			if let Event::CandidatesBatchAdded(CandidatesBatchAdded { count, .. }) = decoded {
+				assert_eq!(count, 2);
+			} else {
+				panic!("encountered unexpected event kind: expected a CandidatesBatchAdded event")
+			}
+		}
+
+		#[test]
+		fn batch_add_candidates_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting
+					.batch_add_candidates(ink_prelude::vec![accounts.charlie])
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_candidates_len(), 2);
+		}
+
+		#[test]
+		fn add_candidate_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.add_candidate(accounts.eve).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_candidates_len(), 2);
+		}
+
+		#[test]
+		fn remove_candidate_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			assert_eq!(voting.total_votes_for(accounts.bob), 3);
+			voting.remove_candidate(accounts.bob).unwrap();
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![accounts.alice, accounts.eve]
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			// 已投出的票不退还
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 7);
+			assert_eq!(
+				voting
+					.vote_candidate(accounts.alice, accounts.bob, 1)
+					.unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 7);
+		}
+
+		#[test]
+		fn withdraw_candidacy_works() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob, accounts.eve];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.alice, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.bob, 3)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.alice, accounts.eve, 2)
+				.unwrap();
+			assert_eq!(voting.left_ticket_num(), 90);
+			set_caller(accounts.bob);
+			voting.withdraw_candidacy().unwrap();
+			assert_eq!(
+				voting.get_candidates(),
+				ink_prelude::vec![accounts.alice, accounts.eve]
+			);
+			assert_eq!(voting.total_votes_for(accounts.bob), 0);
+			assert_eq!(voting.total_votes_for(accounts.eve), 2);
+			// 作废的票回到剩余票数中, 投票人手中的票不变
+			assert_eq!(voting.left_ticket_num(), 93);
+			assert_eq!(voting.voter_ticket_balance(accounts.alice), 5);
+			let emitted_events = test::recorded_events().collect::<Vec<_>>();
+			let event = emitted_events.last().expect("no event emitted");
+			let decoded = <Event as scale::Decode>::decode(&mut &event.data[..])
+				.expect("encountered invalid contract event data buffer");
+			if let Event::CandidateWithdrew(CandidateWithdrew {
+				candidate,
+				votes_returned,
+				..
+			}) = decoded
+			{
+				assert_eq!(candidate, accounts.bob);
+				assert_eq!(votes_returned, 3);
+			} else {
+				panic!("encountered unexpected event kind: expected a CandidateWithdrew event")
+			}
+		}
+
+		#[test]
+		fn withdraw_candidacy_refunds_insured_votes() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.buy_ticket(accounts.django, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.bob, 6)
+				.unwrap();
+			voting
+				.vote_candidate_without_event(accounts.django, accounts.bob, 5)
+				.unwrap();
+			set_caller(accounts.charlie);
+			assert_eq!(
+				voting
+					.insure_vote(accounts.charlie, accounts.bob, 7)
+					.unwrap_err(),
+				VotingError::InsufficientVotes
+			);
+			voting
+				.insure_vote(accounts.charlie, accounts.bob, 4)
+				.unwrap();
+			assert_eq!(voting.get_insured_votes(accounts.charlie, accounts.bob), 4);
+			set_caller(accounts.bob);
+			voting.withdraw_candidacy().unwrap();
+			// 投保的票退回投票人手中, 没有投保的票不退
+			assert_eq!(voting.voter_ticket_balance(accounts.charlie), 8);
+			assert_eq!(voting.voter_ticket_balance(accounts.django), 5);
+			// 其余作废的票回到剩余票数中
+			assert_eq!(voting.left_ticket_num(), 87);
+			assert_eq!(voting.get_insured_votes(accounts.charlie, accounts.bob), 0);
+			assert_eq!(voting.verify_election_integrity(), Vec::new());
+		}
+
+		#[test]
+		fn insure_vote_by_other_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			voting.buy_ticket(accounts.charlie, 10).unwrap();
+			voting.advance_phase().unwrap();
+			voting
+				.vote_candidate_without_event(accounts.charlie, accounts.bob, 6)
+				.unwrap();
+			assert_eq!(
+				voting
+					.insure_vote(accounts.charlie, accounts.bob, 4)
+					.unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_insured_votes(accounts.charlie, accounts.bob), 0);
+		}
+
+		#[test]
+		fn withdraw_candidacy_by_non_candidate_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.eve);
+			assert_eq!(
+				voting.withdraw_candidacy().unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert_eq!(voting.get_candidates_len(), 2);
+		}
+
+		#[test]
+		fn remove_unknown_candidate_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			assert_eq!(
+				voting.remove_candidate(accounts.eve).unwrap_err(),
+				VotingError::NotACandidate
+			);
+			assert_eq!(voting.get_candidates_len(), 2);
+		}
+
+		#[test]
+		fn remove_candidate_by_non_admin_does_not_work() {
+			let accounts = default_accounts();
+			let candidates = ink_prelude::vec![accounts.alice, accounts.bob];
+			let mut voting = Voting::new(candidates, default_config());
+			set_caller(accounts.bob);
+			assert_eq!(
+				voting.remove_candidate(accounts.alice).unwrap_err(),
+				VotingError::Unauthorized
+			);
+			assert_eq!(voting.get_candidates_len(), 2);
+		}
 	}
 }